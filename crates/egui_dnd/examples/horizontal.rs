@@ -1,7 +1,7 @@
 use eframe::egui;
 
 use egui::{CentralPanel, Frame, Label, ScrollArea, TopBottomPanel, Ui, Vec2, Widget};
-use egui_dnd::dnd;
+use egui_dnd::{dnd, DndLayout};
 
 pub fn main() -> eframe::Result<()> {
     let mut items: Vec<_> = (1..1000).collect();
@@ -38,8 +38,8 @@ pub fn main() -> eframe::Result<()> {
                 let width = av_width / columns as f32;
                 let size = Vec2::new(width, width) - ui.spacing().item_spacing;
 
-                let content = |ui: &mut Ui, items: &mut [i32]| {
-                    dnd(ui, "dnd_example").show_vec_sized(
+                let content = |ui: &mut Ui, items: &mut [i32], layout: DndLayout| {
+                    dnd(ui, "dnd_example").with_layout(layout).show_vec_sized(
                         items,
                         size,
                         |ui, item, handle, _state| {
@@ -63,14 +63,15 @@ pub fn main() -> eframe::Result<()> {
                     ScrollArea::vertical().show(ui, |ui| {
                         ui.set_width(ui.available_width());
                         ui.horizontal_wrapped(|ui| {
-                            content(ui, &mut items);
+                            content(ui, &mut items, DndLayout::Auto);
                         });
                     });
                 } else {
                     ScrollArea::horizontal().show(ui, |ui| {
                         ui.set_height(ui.available_height());
                         ui.horizontal(|ui| {
-                            content(ui, &mut items);
+                            // In a single row, only the x coordinate of the pointer matters
+                            content(ui, &mut items, DndLayout::Horizontal);
                         });
                     });
                 }
@@ -0,0 +1,87 @@
+use eframe::egui;
+use egui::{CentralPanel, Id};
+use egui_dnd::dnd;
+
+/// A row as it comes from a server. The server assigns new ids on every sync, the name stays.
+struct Row {
+    server_id: u64,
+    name: &'static str,
+}
+
+fn row_id(row: &Row) -> Id {
+    Id::new(row.server_id)
+}
+
+pub fn main() -> eframe::Result<()> {
+    let mut rows: Vec<Row> = ["alfred", "bernhard", "christian", "dieter", "ernst"]
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| Row {
+            server_id: i as u64,
+            name,
+        })
+        .collect();
+    let mut selected: Vec<Id> = Vec::new();
+    let mut next_server_id = rows.len() as u64;
+    let mut last_sync = 0.0;
+
+    eframe::run_simple_native(
+        "DnD Id Remap Example",
+        Default::default(),
+        move |ctx, _frame| {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.label("Select some rows and drag them. Every two seconds a sync assigns new ids to all rows, the drag continues.");
+
+                // Simulate a background sync that reassigns the ids of all rows, even while the
+                // selected rows are dragged
+                let time = ui.input(|i| i.time);
+                let mut remapped = Vec::new();
+                if time - last_sync > 2.0 {
+                    last_sync = time;
+                    for row in &mut rows {
+                        let old = row_id(row);
+                        row.server_id = next_server_id;
+                        next_server_id += 1;
+                        remapped.push((old, row_id(row)));
+                    }
+                    // Our own state refers to the rows by id as well
+                    for id in &mut selected {
+                        if let Some((_, new)) = remapped.iter().find(|(old, _)| old == id) {
+                            *id = *new;
+                        }
+                    }
+                }
+                ctx.request_repaint();
+
+                let response = dnd(ui, "id_remap")
+                    .with_id_remap(|old| {
+                        remapped
+                            .iter()
+                            .find(|(id, _)| *id == old)
+                            .map_or(old, |(_, new)| *new)
+                    })
+                    .with_selection(selected.iter().copied())
+                    .show_vec_with_id(&mut rows, |row| row.server_id, |ui, row, handle, _state| {
+                        ui.horizontal(|ui| {
+                            let id = row_id(row);
+                            let mut is_selected = selected.contains(&id);
+                            if ui.checkbox(&mut is_selected, "").changed() {
+                                if is_selected {
+                                    selected.push(id);
+                                } else {
+                                    selected.retain(|selected_id| *selected_id != id);
+                                }
+                            }
+                            handle.ui(ui, |ui| {
+                                ui.label(format!("{} (id {})", row.name, row.server_id));
+                            });
+                        });
+                    });
+
+                if let Some(group) = response.dragged_group() {
+                    ui.label(format!("Dragging {} rows", group.len()));
+                }
+            });
+        },
+    )
+}
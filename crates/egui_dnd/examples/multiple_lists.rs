@@ -0,0 +1,43 @@
+use eframe::egui;
+use egui::{CentralPanel, Ui};
+use egui_dnd::{dnd, Handle, ItemState};
+
+fn item_ui(ui: &mut Ui, item: &mut &str, handle: Handle, _state: ItemState) {
+    handle.ui(ui, |ui| {
+        ui.label(*item);
+    });
+}
+
+pub fn main() -> eframe::Result<()> {
+    let mut backlog = vec!["alfred", "bernhard", "christian"];
+    let mut in_progress = vec!["dieter", "ernst"];
+
+    eframe::run_simple_native(
+        "DnD Multiple Lists Example",
+        Default::default(),
+        move |ctx, _frame| {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.columns(2, |columns| {
+                    columns[0].heading("Backlog");
+                    let backlog_response = dnd(&mut columns[0], "backlog")
+                        .with_group("board")
+                        .show_vec(&mut backlog, item_ui);
+
+                    columns[1].heading("In progress");
+                    let in_progress_response = dnd(&mut columns[1], "in_progress")
+                        .with_group("board")
+                        .show_vec(&mut in_progress, item_ui);
+
+                    if let Some(transfer) = in_progress_response.transfer() {
+                        let item = backlog.remove(transfer.from);
+                        in_progress.insert(transfer.to, item);
+                    }
+                    if let Some(transfer) = backlog_response.transfer() {
+                        let item = in_progress.remove(transfer.from);
+                        backlog.insert(transfer.to, item);
+                    }
+                });
+            });
+        },
+    )
+}
@@ -55,9 +55,19 @@ impl Default for MyApp {
                             name: "e_a".to_string(),
                             ..SortableItem::default()
                         },
+                        // Lists can be nested more than one level deep
                         SortableItem {
                             name: "e_b".to_string(),
-                            ..SortableItem::default()
+                            children: Some(vec![
+                                SortableItem {
+                                    name: "e_b_a".to_string(),
+                                    ..SortableItem::default()
+                                },
+                                SortableItem {
+                                    name: "e_b_b".to_string(),
+                                    ..SortableItem::default()
+                                },
+                            ]),
                         },
                         SortableItem {
                             name: "e_c".to_string(),
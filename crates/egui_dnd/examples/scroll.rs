@@ -2,7 +2,7 @@ use eframe::NativeOptions;
 use egui::{CentralPanel, ScrollArea, Sense};
 use std::hash::{Hash, Hasher};
 
-use egui_dnd::dnd;
+use egui_dnd::{dnd, AutoScrollConfig};
 
 struct ItemType {
     number: u32,
@@ -23,20 +23,22 @@ fn main() -> eframe::Result<()> {
         move |ctx, _| {
             CentralPanel::default().show(ctx, |ui| {
                 ScrollArea::vertical().show(ui, |ui| {
-                    dnd(ui, "dnd").show_vec(&mut items, |ui, item, handle, _dragging| {
-                        ui.horizontal(|ui| {
-                            let clicked = handle
-                                .sense(Sense::click())
-                                .ui(ui, |ui| {
-                                    ui.label("grab");
-                                })
-                                .clicked();
-                            if clicked {
-                                println!("clicked {}", item.number);
-                            }
-                            ui.label(&item.number.to_string());
+                    dnd(ui, "dnd")
+                        .with_auto_scroll(AutoScrollConfig::default())
+                        .show_vec(&mut items, |ui, item, handle, _dragging| {
+                            ui.horizontal(|ui| {
+                                let clicked = handle
+                                    .sense(Sense::click())
+                                    .ui(ui, |ui| {
+                                        ui.label("grab");
+                                    })
+                                    .clicked();
+                                if clicked {
+                                    println!("clicked {}", item.number);
+                                }
+                                ui.label(&item.number.to_string());
+                            });
                         });
-                    });
                 })
             });
         },
@@ -0,0 +1,53 @@
+use eframe::egui;
+use egui::{CentralPanel, Grid, Vec2};
+use egui_dnd::{dnd, DndLayout};
+
+const COLUMN_WIDTH: f32 = 100.0;
+
+pub fn main() -> eframe::Result<()> {
+    let headers = ["Name", "Age", "City", "Language"];
+    let rows = [
+        ["alfred", "42", "Berlin", "German"],
+        ["bernhard", "35", "Vienna", "German"],
+        ["christian", "28", "Zurich", "French"],
+        ["dieter", "51", "Munich", "English"],
+    ];
+    // The order the columns are shown in, as indices into headers and the cells of each row.
+    // Only the order is sorted, the table data stays as it is.
+    let mut columns: Vec<usize> = (0..headers.len()).collect();
+
+    eframe::run_simple_native(
+        "DnD Table Columns Example",
+        Default::default(),
+        move |ctx, _frame| {
+            CentralPanel::default().show(ctx, |ui| {
+                let header_size = Vec2::new(COLUMN_WIDTH, ui.spacing().interact_size.y);
+
+                // The headers are a separate row above the grid, so the gap that opens while
+                // dragging doesn't shift the cells of the grid. Every header has the width of
+                // its column, so they stay aligned.
+                ui.horizontal(|ui| {
+                    dnd(ui, "table_columns")
+                        .with_layout(DndLayout::Horizontal)
+                        .show_vec_sized(&mut columns, header_size, |ui, column, handle, _state| {
+                            handle.ui_sized(ui, header_size, |ui| {
+                                ui.strong(headers[*column]);
+                            });
+                        });
+                });
+
+                Grid::new("table")
+                    .min_col_width(COLUMN_WIDTH)
+                    .max_col_width(COLUMN_WIDTH)
+                    .show(ui, |ui| {
+                        for row in &rows {
+                            for column in &columns {
+                                ui.label(row[*column]);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        },
+    )
+}
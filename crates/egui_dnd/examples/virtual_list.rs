@@ -0,0 +1,31 @@
+use eframe::egui;
+use egui::{CentralPanel, ScrollArea};
+use egui_dnd::{dnd, AutoScrollConfig};
+
+pub fn main() -> eframe::Result<()> {
+    let mut items: Vec<_> = (0..100_000).collect();
+
+    eframe::run_simple_native(
+        "DnD Virtual List Example",
+        Default::default(),
+        move |ctx, _frame| {
+            CentralPanel::default().show(ctx, |ui| {
+                let row_height = ui.spacing().interact_size.y;
+                // Only the visible rows are shown, the others are skipped
+                ScrollArea::vertical().show_rows(ui, row_height, items.len(), |ui, visible| {
+                    dnd(ui, "dnd_example")
+                        .with_auto_scroll(AutoScrollConfig::default())
+                        .show_vec_range(&mut items, visible, |ui, item, handle, _state| {
+                            ui.horizontal(|ui| {
+                                ui.set_height(row_height);
+                                handle.ui(ui, |ui| {
+                                    ui.label("drag");
+                                });
+                                ui.label(format!("Item {item}"));
+                            });
+                        });
+                });
+            });
+        },
+    )
+}
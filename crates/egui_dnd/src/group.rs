@@ -0,0 +1,144 @@
+use egui::{Context, Id, Pos2, Vec2};
+
+/// Describes an item that was dragged out of one list of a group and dropped into another one.
+/// The item should be removed from the source list at [DragTransfer::from] and inserted
+/// into the target list at [DragTransfer::to].
+#[derive(Debug, Clone)]
+pub struct DragTransfer {
+    /// Id of the item that was moved
+    pub item_id: Id,
+    /// Id of the list the item was dragged out of. This is `Id::new(id_source)` of the
+    /// id_source passed to [crate::dnd].
+    pub source_list: Id,
+    /// Index of the item in the source list
+    pub from: usize,
+    /// Id of the list the item was dropped into
+    pub target_list: Id,
+    /// Index in the target list the item should be inserted at
+    pub to: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DndGroup {
+    pub(crate) group_id: Id,
+    pub(crate) list_id: Id,
+}
+
+/// Written by the list an item is being dragged out of
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GroupDrag {
+    pub(crate) list_id: Id,
+    pub(crate) item_id: Id,
+    pub(crate) idx: usize,
+    pub(crate) offset: Vec2,
+    pub(crate) size: Vec2,
+    frame_nr: u64,
+}
+
+/// Written by the list the dragged item is currently over, if it isn't the source list
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GroupTarget {
+    pub(crate) list_id: Id,
+    frame_nr: u64,
+}
+
+/// Shared between all lists of a group, stored in egui memory with the group id
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DndGroupState {
+    dragged: Option<GroupDrag>,
+    target: Option<GroupTarget>,
+}
+
+impl DndGroupState {
+    pub(crate) fn load(ctx: &Context, group_id: Id) -> Self {
+        ctx.data_mut(|data| data.get_temp(group_id).unwrap_or_default())
+    }
+
+    pub(crate) fn store(self, ctx: &Context, group_id: Id) {
+        ctx.data_mut(|data| data.insert_temp(group_id, self));
+    }
+
+    /// Returns the item another list of the group is dragging.
+    /// Since lists can be shown in any order, the info may be from this or the last frame.
+    /// Anything older is stale, e.g. because the other list is no longer shown.
+    pub(crate) fn dragged_from_other_list(&self, ctx: &Context, list_id: Id) -> Option<GroupDrag> {
+        self.dragged
+            .filter(|drag| drag.list_id != list_id && drag.frame_nr + 1 >= ctx.frame_nr())
+    }
+
+    /// Returns the other list of the group the item dragged by us is currently over.
+    pub(crate) fn target_other_list(&self, ctx: &Context, list_id: Id) -> Option<GroupTarget> {
+        self.target
+            .filter(|target| target.list_id != list_id && target.frame_nr + 1 >= ctx.frame_nr())
+    }
+
+    pub(crate) fn set_dragged(
+        &mut self,
+        ctx: &Context,
+        list_id: Id,
+        item_id: Id,
+        idx: usize,
+        offset: Vec2,
+        size: Vec2,
+    ) {
+        self.dragged = Some(GroupDrag {
+            list_id,
+            item_id,
+            idx,
+            offset,
+            size,
+            frame_nr: ctx.frame_nr(),
+        });
+    }
+
+    pub(crate) fn set_target(&mut self, ctx: &Context, list_id: Id) {
+        self.target = Some(GroupTarget {
+            list_id,
+            frame_nr: ctx.frame_nr(),
+        });
+    }
+
+    pub(crate) fn clear_dragged(&mut self, list_id: Id) {
+        if self.dragged.map_or(false, |drag| drag.list_id == list_id) {
+            self.dragged = None;
+        }
+    }
+
+    pub(crate) fn clear_target(&mut self, list_id: Id) {
+        if self
+            .target
+            .map_or(false, |target| target.list_id == list_id)
+        {
+            self.target = None;
+        }
+    }
+}
+
+/// State of a list while an item from another list of its group is dragged over it
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignDrag {
+    pub(crate) source: GroupDrag,
+    pub(crate) closest_item: Option<(Id, Pos2)>,
+    pub(crate) hovering_idx: usize,
+    pub(crate) hovering_last_item: bool,
+}
+
+impl ForeignDrag {
+    pub(crate) fn new(source: GroupDrag) -> Self {
+        Self {
+            source,
+            closest_item: None,
+            hovering_idx: 0,
+            hovering_last_item: false,
+        }
+    }
+
+    /// Index the item would be inserted at if it was dropped now
+    pub(crate) fn target_idx(&self) -> usize {
+        if self.hovering_last_item {
+            self.hovering_idx + 1
+        } else {
+            self.hovering_idx
+        }
+    }
+}
@@ -1,14 +1,15 @@
-use egui::{CursorIcon, Id, InnerResponse, LayerId, Order, Pos2, Rect, Sense, Ui, Vec2};
-use egui_animation::animate_position;
+use egui::epaint::Shadow;
+use egui::{Id, InnerResponse, LayerId, Pos2, Rect, Sense, Shape, Style, Ui, Vec2, Visuals};
 
 use crate::state::DragDetectionState;
-use crate::{DragDropUi, Handle, ItemState};
+use crate::{DragDropUi, GrabAnchor, Handle, ItemState};
 
 pub struct Item<'a> {
     id: Id,
     pub state: ItemState,
     dnd_state: &'a mut DragDropUi,
     hovering_over_any_handle: &'a mut bool,
+    animation_time: Option<f32>,
 }
 
 impl<'a> Item<'a> {
@@ -23,9 +24,17 @@ impl<'a> Item<'a> {
             state,
             dnd_state,
             hovering_over_any_handle,
+            animation_time: None,
         }
     }
 
+    /// Sets the duration of this item's animations in seconds, e.g. to let large items settle
+    /// slower than small ones. By default, the animation time of the list is used.
+    pub fn animation_time(mut self, seconds: f32) -> Self {
+        self.animation_time = Some(seconds);
+        self
+    }
+
     pub fn ui(
         self,
         ui: &mut Ui,
@@ -50,9 +59,14 @@ impl<'a> Item<'a> {
         drag_body: impl FnOnce(&mut Ui, Handle, ItemState),
     ) -> ItemResponse {
         let hovering_over_any_handle = self.hovering_over_any_handle;
+        let animation_time = self.animation_time;
         let id = self.id;
         let index = self.state.index;
-        let last_pointer_pos = self.dnd_state.detection_state.last_pointer_pos();
+        let total = self.state.total;
+        let drag_pointer_pos = self.dnd_state.drag_pointer_pos(ui);
+        let copy_on_drag = self.dnd_state.copy_on_drag;
+        let grab_anchor = self.dnd_state.grab_anchor;
+        let lifted = self.dnd_state.is_lifted();
         if let DragDetectionState::Dragging {
             id: dragging_id,
             offset,
@@ -60,26 +74,23 @@ impl<'a> Item<'a> {
         } = &mut self.dnd_state.detection_state
         {
             // Draw the item item in it's original position in the first frame to avoid flickering
-            if id == *dragging_id {
-                ui.output_mut(|o| o.cursor_icon = CursorIcon::Grabbing);
-
-                let _layer_id = LayerId::new(Order::Tooltip, id);
+            if id == *dragging_id && !copy_on_drag && lifted {
+                if let Some(cursor) = self.dnd_state.cursor(self.dnd_state.dragging_cursor) {
+                    ui.output_mut(|o| o.cursor_icon = cursor);
+                }
 
-                let pointer_pos = ui
-                    .ctx()
-                    .pointer_hover_pos()
-                    .or(last_pointer_pos)
-                    .unwrap_or_else(|| ui.next_widget_position());
+                let pointer_pos = drag_pointer_pos.unwrap_or_else(|| ui.next_widget_position());
                 let position = pointer_pos + *offset;
+                let position = self.dnd_state.floating_item_pos(position);
 
                 // We animate so the animated position is updated, even though we don't use it here.
-                animate_position(
+                self.dnd_state.animate_position(
                     ui,
                     id,
                     position,
-                    ui.style().animation_time,
-                    simple_easing::cubic_in_out,
+                    self.dnd_state.settle_easing,
                     false,
+                    animation_time,
                 );
 
                 let InnerResponse { inner: rect, .. } = Self::draw_floating_at_position(
@@ -93,6 +104,25 @@ impl<'a> Item<'a> {
                     drag_body,
                 );
 
+                // Items can change their size while dragged, e.g. if they show a different ui
+                // while dragged. The gap that opens in the list uses the measured size, so the
+                // other items move by exactly the space the dragged item takes up.
+                if let DragDetectionState::Dragging {
+                    dragged_item_size,
+                    offset,
+                    ..
+                } = &mut self.dnd_state.detection_state
+                {
+                    *dragged_item_size = rect.size();
+                    // The size is only known once the item was shown, so the anchor is applied
+                    // from the second frame of the drag on
+                    match grab_anchor {
+                        GrabAnchor::Pointer => {}
+                        GrabAnchor::Center => *offset = -rect.size() / 2.0,
+                        GrabAnchor::TopLeft => *offset = Vec2::ZERO,
+                    }
+                }
+
                 ui.allocate_space(rect.size());
 
                 let rect = Rect::from_min_size(ui.next_widget_position(), rect.size());
@@ -111,13 +141,13 @@ impl<'a> Item<'a> {
                     (ui.next_widget_position(), None)
                 };
 
-                let position = animate_position(
+                let position = self.dnd_state.animate_position(
                     ui,
                     id,
                     end_pos,
-                    ui.style().animation_time,
-                    simple_easing::cubic_out,
+                    self.dnd_state.return_easing,
                     false,
+                    animation_time,
                 );
 
                 let InnerResponse { inner: rect, .. } = Self::draw_floating_at_position(
@@ -147,6 +177,23 @@ impl<'a> Item<'a> {
         }
 
         let was_dragging = self.dnd_state.detection_state.is_dragging();
+        // If the pointer is pressed again before the items arrived, they keep animating, so
+        // they don't jump when a new drag starts, see DragDropUi::with_snap_on_interrupt
+        let returning = matches!(
+            self.dnd_state.detection_state,
+            DragDetectionState::TransitioningBackAfterDragFinished { .. }
+        ) || (self.dnd_state.interrupted_return
+            && self.dnd_state.detection_state.is_evaluating_drag());
+        // We also animate while the dropped item transitions back, so items that changed their
+        // position when the item was dropped (e.g. in ReorderMode::Swap) move there smoothly
+        // Pinned items jump to their position, so they don't move while the items around them do
+        let animate = self.state.displaceable
+            && (was_dragging
+                || returning
+                || matches!(
+                    self.dnd_state.detection_state,
+                    DragDetectionState::KeyboardDragging { .. }
+                ));
 
         let rect = if let Some(size) = size {
             // We need to do it like this because in some layouts
@@ -154,54 +201,64 @@ impl<'a> Item<'a> {
             // of the top left corner
             let (_, rect) = ui.allocate_space(size);
 
-            let animated_position = animate_position(
+            let animated_position = self.dnd_state.animate_position(
                 ui,
                 id,
                 rect.min,
-                ui.style().animation_time,
-                simple_easing::cubic_in_out,
+                self.dnd_state.settle_easing,
                 true,
+                animation_time,
             );
 
-            let position = if self.dnd_state.detection_state.is_dragging() {
-                animated_position
-            } else {
-                rect.min
-            };
+            let position = if animate { animated_position } else { rect.min };
+            // While the items move back, a new drag grabs the item where it's shown
+            let handle_position = if returning { position } else { rect.min };
 
             let mut child = ui.child_ui(rect, *ui.layout());
 
-            child.allocate_ui_at_rect(Rect::from_min_size(position, rect.size()), |ui| {
-                drag_body(
-                    ui,
-                    Handle::new(
-                        id,
-                        index,
-                        self.dnd_state,
-                        hovering_over_any_handle,
-                        rect.min,
-                    ),
-                    self.state,
+            let body_rect = child
+                .allocate_ui_at_rect(Rect::from_min_size(position, rect.size()), |ui| {
+                    drag_body(
+                        ui,
+                        Handle::new(
+                            id,
+                            index,
+                            total,
+                            self.dnd_state,
+                            hovering_over_any_handle,
+                            handle_position,
+                        ),
+                        self.state,
+                    )
+                })
+                .response
+                .rect;
+
+            if self.dnd_state.whole_item_as_handle {
+                Handle::new(
+                    id,
+                    index,
+                    total,
+                    self.dnd_state,
+                    hovering_over_any_handle,
+                    handle_position,
                 )
-            });
+                .ui_for_item_rect(ui, body_rect);
+            }
 
             rect
         } else {
             let position = ui.next_widget_position();
-            let animated_position = animate_position(
+            let animated_position = self.dnd_state.animate_position(
                 ui,
                 id,
                 position,
-                ui.style().animation_time,
-                simple_easing::cubic_in_out,
+                self.dnd_state.settle_easing,
                 true,
+                animation_time,
             );
 
-            let position = if self.dnd_state.detection_state.is_dragging() {
-                animated_position
-            } else {
-                position
-            };
+            let position = if animate { animated_position } else { position };
 
             let size = ui.available_size();
 
@@ -212,6 +269,7 @@ impl<'a> Item<'a> {
                     Handle::new(
                         id,
                         index,
+                        total,
                         self.dnd_state,
                         hovering_over_any_handle,
                         animated_position,
@@ -220,9 +278,25 @@ impl<'a> Item<'a> {
                 )
             });
 
+            if self.dnd_state.whole_item_as_handle {
+                Handle::new(
+                    id,
+                    index,
+                    total,
+                    self.dnd_state,
+                    hovering_over_any_handle,
+                    animated_position,
+                )
+                .ui_for_item_rect(ui, response.response.rect);
+            }
+
             ui.allocate_space(response.response.rect.size()).1
         };
 
+        if copy_on_drag && self.dnd_state.detection_state.is_dragging_item(id) {
+            Self::draw_copy_ghost(self.dnd_state, ui, id, rect.size());
+        }
+
         if !was_dragging && self.dnd_state.detection_state.is_dragging() {
             if let DragDetectionState::Dragging {
                 dragged_item_size, ..
@@ -231,11 +305,44 @@ impl<'a> Item<'a> {
                 // We set this here because we don't know the size in the handle
                 *dragged_item_size = rect.size();
             }
+            // Start the lift animation at 0.0, it would start at 1.0 otherwise
+            self.dnd_state.lift_animation(ui, id, false);
         }
 
         ItemResponse(rect)
     }
 
+    /// Paints a ghost of the item's size at the pointer, while the item itself stays in the list,
+    /// see [DragDropUi::with_copy_on_drag].
+    fn draw_copy_ghost(dnd_state: &DragDropUi, ui: &Ui, id: Id, size: Vec2) {
+        let pointer_pos = dnd_state.drag_pointer_pos(ui);
+        let (Some(pointer_pos), DragDetectionState::Dragging { offset, .. }) =
+            (pointer_pos, &dnd_state.detection_state)
+        else {
+            return;
+        };
+
+        if let Some(cursor) = dnd_state.cursor(dnd_state.dragging_cursor) {
+            ui.output_mut(|o| o.cursor_icon = cursor);
+        }
+
+        let rect = Rect::from_min_size(dnd_state.floating_item_pos(pointer_pos + *offset), size);
+        let mut painter = ui.ctx().layer_painter(LayerId::new(
+            dnd_state.drag_layer,
+            id.with("dnd_copy_ghost"),
+        ));
+        if let Some(clip) = dnd_state.floating_clip {
+            painter.set_clip_rect(clip.intersect(painter.clip_rect()));
+        }
+        let visuals = &ui.visuals().widgets.active;
+        painter.rect(
+            rect,
+            visuals.rounding,
+            visuals.bg_fill.gamma_multiply(dnd_state.drag_opacity * 0.5),
+            visuals.bg_stroke,
+        );
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn draw_floating_at_position(
         state: ItemState,
@@ -247,24 +354,127 @@ impl<'a> Item<'a> {
         size: Option<Vec2>,
         body: impl FnOnce(&mut Ui, Handle, ItemState),
     ) -> InnerResponse<Rect> {
-        egui::Area::new("draggable_item")
+        let hovering_remove_zone = dnd_state.hovering_remove_zone;
+        let size = size.or(dnd_state.detection_state.dragged_item_size());
+        let lift = dnd_state.lift_animation(ui, id, dnd_state.detection_state.is_dragging_item(id));
+        let scale = 1.0 + (dnd_state.drag_scale - 1.0) * lift;
+        let shadow = dnd_state.drag_shadow;
+        let clip = dnd_state.floating_clip;
+        // Scale around the center of the item
+        let area_pos = size.map_or(pos, |size| pos - size * (scale - 1.0) / 2.0);
+
+        let InnerResponse {
+            inner: rect,
+            response,
+        } = egui::Area::new("draggable_item")
+            .order(dnd_state.drag_layer)
             .interactable(false)
-            .fixed_pos(pos)
+            .fixed_pos(area_pos)
             .show(ui.ctx(), |ui| {
-                ui.scope(|ui| {
-                    if let Some(size) = size.or(dnd_state.detection_state.dragged_item_size()) {
-                        ui.set_max_size(size);
-                    }
-                    body(
-                        ui,
-                        Handle::new(id, state.index, dnd_state, hovering_over_any_handle, pos),
-                        state,
-                    )
-                })
-                .response
-                .rect
-            })
+                if let Some(clip) = clip {
+                    ui.set_clip_rect(clip.intersect(ui.clip_rect()));
+                }
+                // Reserve a shape below the item, we only know the rect after drawing it
+                let shadow_idx = shadow.map(|_| ui.painter().add(Shape::Noop));
+
+                let rect = ui
+                    .scope(|ui| {
+                        if dnd_state.drag_opacity < 1.0 {
+                            multiply_opacity(ui.visuals_mut(), dnd_state.drag_opacity);
+                        }
+                        if scale != 1.0 {
+                            scale_style(ui.style_mut(), scale);
+                        }
+                        if let Some(size) = size {
+                            ui.set_max_size(size * scale);
+                        }
+                        body(
+                            ui,
+                            Handle::new(
+                                id,
+                                state.index,
+                                state.total,
+                                dnd_state,
+                                hovering_over_any_handle,
+                                pos,
+                            ),
+                            ItemState {
+                                floating: true,
+                                ..state
+                            },
+                        )
+                    })
+                    .response
+                    .rect;
+
+                if let (Some(shadow), Some(shadow_idx)) = (shadow, shadow_idx) {
+                    let shadow = Shadow {
+                        extrusion: shadow.extrusion,
+                        color: shadow.color.gamma_multiply(lift),
+                    };
+                    ui.painter().set(
+                        shadow_idx,
+                        shadow.tessellate(rect, ui.visuals().widgets.noninteractive.rounding),
+                    );
+                }
+
+                if hovering_remove_zone {
+                    ui.painter().rect_filled(
+                        rect,
+                        ui.visuals().widgets.noninteractive.rounding,
+                        ui.visuals().error_fg_color.gamma_multiply(0.3),
+                    );
+                }
+
+                rect
+            });
+
+        // The item should take up its unscaled size in the list
+        InnerResponse {
+            inner: Rect::from_min_size(rect.min, rect.size() / scale),
+            response,
+        }
     }
 }
 
 pub struct ItemResponse(pub(crate) Rect);
+
+fn scale_style(style: &mut Style, scale: f32) {
+    for font_id in style.text_styles.values_mut() {
+        font_id.size *= scale;
+    }
+    style.spacing.item_spacing *= scale;
+    style.spacing.button_padding *= scale;
+    style.spacing.interact_size *= scale;
+    style.spacing.icon_width *= scale;
+    style.spacing.icon_width_inner *= scale;
+    style.spacing.icon_spacing *= scale;
+}
+
+fn multiply_opacity(visuals: &mut Visuals, opacity: f32) {
+    let widgets = &mut visuals.widgets;
+    for widget in [
+        &mut widgets.noninteractive,
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        widget.bg_fill = widget.bg_fill.gamma_multiply(opacity);
+        widget.weak_bg_fill = widget.weak_bg_fill.gamma_multiply(opacity);
+        widget.bg_stroke.color = widget.bg_stroke.color.gamma_multiply(opacity);
+        widget.fg_stroke.color = widget.fg_stroke.color.gamma_multiply(opacity);
+    }
+    visuals.override_text_color = visuals
+        .override_text_color
+        .map(|color| color.gamma_multiply(opacity));
+    visuals.selection.bg_fill = visuals.selection.bg_fill.gamma_multiply(opacity);
+    visuals.selection.stroke.color = visuals.selection.stroke.color.gamma_multiply(opacity);
+    visuals.hyperlink_color = visuals.hyperlink_color.gamma_multiply(opacity);
+    visuals.faint_bg_color = visuals.faint_bg_color.gamma_multiply(opacity);
+    visuals.extreme_bg_color = visuals.extreme_bg_color.gamma_multiply(opacity);
+    visuals.code_bg_color = visuals.code_bg_color.gamma_multiply(opacity);
+    visuals.window_fill = visuals.window_fill.gamma_multiply(opacity);
+    visuals.window_stroke.color = visuals.window_stroke.color.gamma_multiply(opacity);
+    visuals.panel_fill = visuals.panel_fill.gamma_multiply(opacity);
+}
@@ -1,16 +1,23 @@
 use crate::item::{Item, ItemResponse};
-use crate::state::DragDetectionState;
+use crate::state::{DndLayout, DragDetectionState, ReorderMode};
 use crate::{DragDropUi, ItemState};
-use egui::{Id, Layout, Pos2, Rect, Ui, Vec2};
+use egui::{pos2, Id, Layout, Pos2, Rect, Shape, Ui, Vec2};
+use std::collections::HashSet;
 
 pub struct ItemIterator<'a> {
     state: &'a mut DragDropUi,
+    /// Used to detect duplicate ids, which break the animations and the drag detection
+    seen_ids: HashSet<Id>,
+    /// True if two items had the same id, see [crate::DragDropResponse::has_duplicate_ids]
+    pub(crate) duplicate_ids: bool,
     dragged_item_rect: Option<Rect>,
     hovering_item: Option<(Id, Pos2)>,
     layout: Layout,
     set_next_item_as_hovering_above: bool,
     pub(crate) hovering_last_item: bool,
     pub(crate) last_item: Option<(usize, Id, Pos2)>,
+    /// Used to wrap the dragged item around, see [DragDropUi::with_wrap_around]
+    pub(crate) first_item: Option<(usize, Id, Pos2)>,
 
     pub(crate) mark_next_as_closest_item: Option<(f32, Pos2)>,
 
@@ -18,6 +25,37 @@ pub struct ItemIterator<'a> {
     pub(crate) is_after_hovered_item: bool,
     pub(crate) hovering_over_any_handle: bool,
     pub(crate) source_item: Option<(usize, Id)>,
+    /// Union of all item rects, including the space allocated for the dragged item
+    pub(crate) list_rect: Rect,
+    /// The space allocated where the dragged item would be inserted
+    pub(crate) gap_rect: Option<Rect>,
+    /// Indices of the items that are locked, see [DragDropUi::with_locked_items]
+    pub(crate) locked_indices: Vec<usize>,
+    /// Indices of the dividers, see [DragDropUi::with_dividers]
+    pub(crate) divider_indices: Vec<usize>,
+    /// Indices of the pinned items, see [DragDropUi::with_pinned_items]
+    pub(crate) pinned_indices: Vec<usize>,
+    /// Position of the first item, used to calculate the cell under the pointer in [DndLayout::Grid]
+    grid_origin: Option<Pos2>,
+    /// In [DndLayout::Grid], the [crate::DragUpdate::to] index of the cell under the pointer,
+    /// or None if the gap is already there
+    pub(crate) grid_target: Option<usize>,
+    /// The item below the pointer, other than the dragged item
+    pub(crate) pointer_over_item: Option<(usize, Id)>,
+    /// The item the dragged item would be dropped into, see [DragDropUi::with_drop_into]
+    pub(crate) into_target: Option<(usize, Rect)>,
+    /// In [ReorderMode::Swap], the item the dragged item is currently over
+    pub(crate) swap_target: Option<(usize, Rect)>,
+    /// Number of items, passed to the items in [ItemState::total]
+    total: Option<usize>,
+    /// Indices of the dragged item and the selected items dragged with it
+    pub(crate) group_indices: Vec<usize>,
+    /// Space taken up by the selected items dragged along with the dragged item
+    pub(crate) group_extent: f32,
+    /// Ids of all items in the order they were shown
+    pub(crate) item_ids: Vec<Id>,
+    /// Rects of the items shown in the list, see [crate::DragDropResponse::item_rects]
+    pub(crate) item_rects: Vec<(Id, Rect)>,
 
     #[allow(clippy::type_complexity)]
     pub(crate) closest_item: Option<(f32, Option<(usize, Id, Pos2)>)>,
@@ -29,18 +67,26 @@ impl<'a> ItemIterator<'a> {
             DragDetectionState::Dragging {
                 closest_item: item, ..
             } => Some(item),
-            _ => None,
+            _ => state
+                .foreign_drag
+                .as_ref()
+                .and_then(|foreign| foreign.closest_item),
         };
 
         let hovering_last_item = match state.detection_state {
             DragDetectionState::Dragging {
                 hovering_last_item, ..
             } => hovering_last_item,
-            _ => false,
+            _ => state
+                .foreign_drag
+                .as_ref()
+                .map_or(false, |foreign| foreign.hovering_last_item),
         };
 
         Self {
             state,
+            seen_ids: HashSet::new(),
+            duplicate_ids: false,
             dragged_item_rect,
             layout,
             set_next_item_as_hovering_above: false,
@@ -49,14 +95,35 @@ impl<'a> ItemIterator<'a> {
             mark_next_as_closest_item: None,
             hovering_last_item,
             last_item: None,
+            first_item: None,
 
             is_after_dragged_item: false,
             is_after_hovered_item: false,
             hovering_over_any_handle: false,
             source_item: None,
+            list_rect: Rect::NOTHING,
+            gap_rect: None,
+            locked_indices: Vec::new(),
+            divider_indices: Vec::new(),
+            pinned_indices: Vec::new(),
+            grid_origin: None,
+            grid_target: None,
+            pointer_over_item: None,
+            into_target: None,
+            swap_target: None,
+            total: None,
+            group_indices: Vec::new(),
+            group_extent: 0.0,
+            item_ids: Vec::new(),
+            item_rects: Vec::new(),
         }
     }
 
+    /// Sets the number of items in the list, so items can check [ItemState::is_last].
+    pub fn set_total(&mut self, total: usize) {
+        self.total = Some(total);
+    }
+
     pub fn next(
         &mut self,
         ui: &mut Ui,
@@ -65,7 +132,53 @@ impl<'a> ItemIterator<'a> {
         add_surrounding_space_automatically: bool,
         content: impl FnOnce(&mut Ui, Item) -> ItemResponse,
     ) {
-        let is_dragged_item = self.state.detection_state.is_dragging_item(id);
+        let displaceable = !self.state.is_pinned(id);
+        self.next_displaceable(
+            ui,
+            id,
+            idx,
+            add_surrounding_space_automatically,
+            displaceable,
+            content,
+        );
+    }
+
+    /// Same as [ItemIterator::next], but if `displaceable` is false, the item keeps its position
+    /// while other items are reordered around it, like the items set with
+    /// [crate::Dnd::with_pinned_items]. Locked items are never displaced.
+    pub fn next_displaceable(
+        &mut self,
+        ui: &mut Ui,
+        id: Id,
+        idx: usize,
+        add_surrounding_space_automatically: bool,
+        displaceable: bool,
+        content: impl FnOnce(&mut Ui, Item) -> ItemResponse,
+    ) {
+        // Other items move past locked items like past pinned items, see
+        // [crate::Dnd::with_locked_items]
+        let displaceable = displaceable && !self.state.is_locked(id);
+
+        if !self.seen_ids.insert(id) {
+            self.duplicate_ids = true;
+        }
+
+        self.item_ids.push(id);
+
+        // Until the pointer moves, the item of a drag that was started by
+        // [crate::DragDropConfig::click_tolerance_timeout] is shown like any other item
+        let is_dragged_item = self.state.is_lifted_item(id);
+
+        if self.state.is_in_dragged_group(id) {
+            self.group_indices.push(idx);
+            if !is_dragged_item {
+                if let Some(dragged_item_rect) = self.dragged_item_rect {
+                    // The item isn't part of the list while it's dragged, so it can't be a target
+                    self.show_group_item(ui, id, idx, dragged_item_rect, content);
+                    return;
+                }
+            }
+        }
 
         if let Some((distance, pos)) = self.mark_next_as_closest_item {
             self.mark_next_as_closest_item = None;
@@ -82,22 +195,42 @@ impl<'a> ItemIterator<'a> {
             }
         }
 
+        if self.grid_origin.is_none() {
+            self.grid_origin = Some(ui.cursor().min);
+            if let (DndLayout::Grid { columns }, Some(dragged_item_rect)) =
+                (self.state.layout, self.dragged_item_rect)
+            {
+                self.grid_target = self.grid_target(ui, dragged_item_rect, columns);
+            }
+        }
+
         if add_surrounding_space_automatically {
             self.space_before(ui, id, |_ui, _space| {})
         }
 
+        // Reserved before the item is shown, so the highlight is painted behind it
+        let highlight = self
+            .state
+            .drop_highlight_color(ui, id)
+            .map(|color| (ui.painter().add(Shape::Noop), color));
+
         let dragging = self.state.detection_state.is_dragging();
+        // When copying, the dragged item stays in the list and a ghost follows the pointer
+        let copy_on_drag = self.state.copy_on_drag;
 
         let item = Item::new(
             id,
             ItemState {
                 dragged: is_dragged_item,
                 index: idx,
+                total: self.total,
+                floating: false,
+                displaceable,
             },
             self.state,
             &mut self.hovering_over_any_handle,
         );
-        let rect = if is_dragged_item {
+        let rect = if is_dragged_item && !copy_on_drag {
             if let Some((_id, pos)) = self.hovering_item {
                 let mut child = ui.child_ui(ui.available_rect_before_wrap(), *ui.layout());
                 let start = ui.next_widget_position();
@@ -115,6 +248,12 @@ impl<'a> ItemIterator<'a> {
             content(ui, item).0
         };
 
+        if let Some((shape_idx, color)) = highlight {
+            let rounding = ui.visuals().widgets.hovered.rounding;
+            ui.painter()
+                .set(shape_idx, Shape::rect_filled(rect, rounding, color));
+        }
+
         if dragging != self.state.detection_state.is_dragging() {
             self.set_next_item_as_hovering_above = true;
         }
@@ -124,7 +263,16 @@ impl<'a> ItemIterator<'a> {
         }
 
         if let Some(dragged_item_rect) = self.dragged_item_rect {
-            if self.layout.main_wrap {
+            if self.state.reorder_mode == ReorderMode::Swap {
+                // The gap stays at the source position, we only need to know what we are over
+                if !is_dragged_item && rect.contains(dragged_item_rect.center()) {
+                    self.swap_target = Some((idx, rect));
+                }
+            } else if let DndLayout::Grid { .. } = self.state.layout {
+                if self.grid_target == Some(idx) {
+                    self.closest_item = Some((0.0, Some((idx, id, rect.min))));
+                }
+            } else if self.state.layout == DndLayout::Auto && self.layout.main_wrap {
                 if rect.contains(dragged_item_rect.center()) {
                     if self.is_after_hovered_item {
                         self.mark_next_as_closest_item = Some((0.0, rect.min));
@@ -142,12 +290,134 @@ impl<'a> ItemIterator<'a> {
             self.source_item = Some((idx, id));
         }
 
+        if let Some(pointer_pos) = ui
+            .input(|i| i.pointer.hover_pos())
+            .filter(|pos| !is_dragged_item && rect.contains(*pos))
+        {
+            self.pointer_over_item = Some((idx, id));
+
+            if let (Some(band), Some(_)) = (self.state.drop_into, self.dragged_item_rect) {
+                let fraction = if self.state.layout.is_horizontal(&self.layout) {
+                    (pointer_pos.x - rect.min.x) / rect.width()
+                } else {
+                    (pointer_pos.y - rect.min.y) / rect.height()
+                };
+                if (fraction - 0.5).abs() <= band / 2.0 {
+                    self.into_target = Some((idx, rect));
+                }
+            }
+        }
+
+        if self.state.is_locked(id) {
+            self.locked_indices.push(idx);
+        }
+        if self.state.is_divider(id) {
+            self.divider_indices.push(idx);
+        }
+        if !displaceable {
+            self.pinned_indices.push(idx);
+        }
+
+        self.list_rect = self.list_rect.union(rect);
+        self.item_rects.push((id, rect));
+
         self.last_item = Some((idx, id, rect.min));
+        self.first_item.get_or_insert((idx, id, rect.min));
+    }
+
+    /// Shows a selected item that is dragged along with the dragged item, stacked below it.
+    fn show_group_item(
+        &mut self,
+        ui: &mut Ui,
+        id: Id,
+        idx: usize,
+        dragged_item_rect: Rect,
+        content: impl FnOnce(&mut Ui, Item) -> ItemResponse,
+    ) {
+        let horizontal = self.state.layout.is_horizontal(&self.layout);
+        let spacing = ui.spacing().item_spacing;
+        let pos = if horizontal {
+            pos2(
+                dragged_item_rect.max.x + spacing.x + self.group_extent,
+                dragged_item_rect.min.y,
+            )
+        } else {
+            pos2(
+                dragged_item_rect.min.x,
+                dragged_item_rect.max.y + spacing.y + self.group_extent,
+            )
+        };
+        let max_size = ui.available_size_before_wrap();
+        let order = self.state.drag_layer;
+        let clip = self.state.floating_clip;
+
+        let item = Item::new(
+            id,
+            ItemState {
+                dragged: true,
+                index: idx,
+                total: self.total,
+                // Stacked below the floating item
+                floating: true,
+                displaceable: true,
+            },
+            self.state,
+            &mut self.hovering_over_any_handle,
+        );
+        let rect = egui::Area::new(id.with("dnd_group_item"))
+            .order(order)
+            .interactable(false)
+            .fixed_pos(pos)
+            .show(ui.ctx(), |ui| {
+                if let Some(clip) = clip {
+                    ui.set_clip_rect(clip.intersect(ui.clip_rect()));
+                }
+                ui.set_max_size(max_size);
+                content(ui, item).0
+            })
+            .inner;
+
+        self.group_extent += if horizontal {
+            rect.width() + spacing.x
+        } else {
+            rect.height() + spacing.y
+        };
+    }
+
+    /// Maps the center of the dragged item to a cell of the grid. This doesn't depend on where
+    /// the items are currently shown, so moving the gap can't change the target.
+    fn grid_target(&self, ui: &Ui, dragged_item_rect: Rect, columns: usize) -> Option<usize> {
+        let origin = self.grid_origin?;
+        let cell_size = dragged_item_rect.size() + ui.spacing().item_spacing;
+        let center = dragged_item_rect.center() - origin;
+        let col = ((center.x / cell_size.x).floor().max(0.0) as usize).min(columns.max(1) - 1);
+        let row = (center.y / cell_size.y).floor().max(0.0) as usize;
+        let landing_idx = row * columns.max(1) + col;
+
+        let (source_idx, current_target) = match &self.state.detection_state {
+            DragDetectionState::Dragging {
+                source_idx,
+                hovering_idx,
+                hovering_last_item,
+                ..
+            } => (
+                Some(*source_idx),
+                hovering_idx + usize::from(*hovering_last_item),
+            ),
+            _ => (None, self.state.foreign_drag.as_ref()?.target_idx()),
+        };
+        // The dragged item is still part of the list, so the target is behind it
+        let target = match source_idx {
+            Some(source_idx) if landing_idx > source_idx => landing_idx + 1,
+            _ => landing_idx,
+        };
+
+        (target != current_target).then_some(target)
     }
 
     fn get_distance(&mut self, dragged_item_rect: Rect, rect: Rect) -> (f32, bool) {
         let size_difference = dragged_item_rect.size() - rect.size();
-        let (distance, mark_next) = if self.layout.is_horizontal() {
+        let (distance, mark_next) = if self.state.layout.is_horizontal(&self.layout) {
             let distance = dragged_item_rect.center().x - rect.center().x;
             let mark_next = rect.center().x < dragged_item_rect.center().x;
             (distance, mark_next)
@@ -182,20 +452,63 @@ impl<'a> ItemIterator<'a> {
         id: Id,
         content: impl FnOnce(&mut Ui, Vec2),
     ) {
-        if let Some((hovering_id, _pos)) = self.hovering_item {
-            if hovering_id == id {
-                if let Some(dragged_item_rect) = self.dragged_item_rect {
-                    let rect = ui
-                        .allocate_ui(dragged_item_rect.size(), |ui| {
-                            ui.set_min_size(dragged_item_rect.size());
-                            content(ui, dragged_item_rect.size());
-                        })
-                        .response
-                        .rect;
-                    let (distance, _mark_next) = self.get_distance(dragged_item_rect, rect);
-                    self.check_closest_item(distance, rect.min, None, false);
-                }
+        let Some(dragged_item_rect) = self.dragged_item_rect else {
+            return;
+        };
+        let is_gap = self
+            .hovering_item
+            .map_or(false, |(hovering_id, _pos)| hovering_id == id);
+
+        // The gap opens smoothly at the new position and closes at the old one
+        let open = self.state.gap_animation(ui, id, is_gap);
+        if open <= 0.0 {
+            return;
+        }
+        // The item spacing is animated as well, so there is no jump when the gap disappears
+        let spacing = ui.spacing().item_spacing;
+        let mut size = self
+            .state
+            .placeholder_size
+            .unwrap_or(dragged_item_rect.size());
+        // The gap makes room for the selected items dragged along as well
+        let group_extent = self.state.dragged_group_extent;
+        let main_axis_size = if self.state.layout.is_horizontal(&self.layout) {
+            size.x += group_extent;
+            size.x = ((size.x + spacing.x) * open - spacing.x).max(0.0);
+            size.x
+        } else {
+            size.y += group_extent;
+            size.y = ((size.y + spacing.y) * open - spacing.y).max(0.0);
+            size.y
+        };
+
+        if !is_gap {
+            if main_axis_size > 0.0 {
+                ui.allocate_space(size);
             }
+            return;
+        }
+
+        let rect = ui
+            .allocate_ui(size, |ui| {
+                ui.set_min_size(size);
+                content(ui, size);
+            })
+            .response
+            .rect;
+        self.list_rect = self.list_rect.union(rect);
+        self.gap_rect = Some(rect);
+        if !matches!(self.state.layout, DndLayout::Grid { .. }) {
+            let (distance, _mark_next) = self.get_distance(dragged_item_rect, rect);
+            // The gap is the current target, giving it a head start means the item has to move
+            // past the midpoint to the next item by the hysteresis before the target changes
+            let item_size = if self.state.layout.is_horizontal(&self.layout) {
+                dragged_item_rect.width()
+            } else {
+                dragged_item_rect.height()
+            };
+            let distance = distance - 2.0 * self.state.reorder_hysteresis * item_size;
+            self.check_closest_item(distance, rect.min, None, false);
         }
     }
 
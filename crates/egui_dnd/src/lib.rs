@@ -2,15 +2,23 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-use egui::{Id, Ui};
-pub use state::{DragDropConfig, DragDropItem, DragDropResponse, DragUpdate, Handle};
+use egui::{Id, Rect, Ui, Vec2};
+pub use group::DragTransfer;
+pub use state::{
+    AutoScrollConfig, CancellationReason, DndEvent, DndLayout, DragDropConfig, DragDropItem,
+    DragDropResponse, DragUpdate, DropKind, DropPosition, GrabAnchor, Grip, Handle, HandleState,
+    ReorderMode,
+};
 
 use crate::item_iterator::ItemIterator;
-use crate::state::DragDropUi;
+use crate::state::{DndCallbacks, DragDropUi};
 use std::hash::Hash;
+use std::ops::Range;
 
+mod group;
 mod item;
 mod item_iterator;
+mod nesting;
 mod state;
 /// Helper functions to support the drag and drop functionality
 pub mod utils;
@@ -18,14 +26,23 @@ pub mod utils;
 /// Helper struct for ease of use.
 pub struct Dnd<'a> {
     id: Id,
+    list_id: Id,
     ui: &'a mut Ui,
     drag_drop_ui: DragDropUi,
+    callbacks: DndCallbacks<'a>,
 }
 
 /// Main entry point for the drag and drop functionality.
 /// Loads and saves it's state from egui memory.
 /// Use either [Dnd::show] or [Dnd::show_vec] to display the drag and drop UI.
+/// `id_source` can be anything that implements [Hash], e.g. a typed key or an [Id] you already
+/// have, it doesn't have to be a string. In reusable components, derive it from the id of the
+/// ui, e.g. `dnd(ui, ui.id().with("list"))`, so two instances don't share their state.
 /// You can use [Dnd::with_mouse_config] or [Dnd::with_touch_config] to configure the drag detection.
+///
+/// Lists can be nested inside the items of other lists, to any depth, as long as each list has
+/// its own id_source. If a handle is part of more than one list, e.g. because the whole item of
+/// the outer list is the handle, the innermost list starts the drag. See the nested example.
 /// Example usage:
 /// ```rust;no_run
 /// use std::hash::Hash;
@@ -52,17 +69,48 @@ pub struct Dnd<'a> {
 /// }
 /// ```
 pub fn dnd(ui: &mut Ui, id_source: impl Hash) -> Dnd {
-    let id = Id::new(id_source).with("dnd");
-    let dnd_ui: DragDropUi =
+    let list_id = Id::new(id_source);
+    let id = list_id.with("dnd");
+    let mut dnd_ui: DragDropUi =
         ui.data_mut(|data| (*data.get_temp_mut_or_default::<DragDropUi>(id)).clone());
+    dnd_ui.id = id;
 
     Dnd {
         id,
+        list_id,
         ui,
         drag_drop_ui: dnd_ui,
+        callbacks: DndCallbacks::default(),
     }
 }
 
+/// The simplest way to make a Vec reorderable: shows each item with `item_ui` and sorts `items`
+/// while the item is dragged. The whole item is the handle.
+/// Returns the index the dropped item was picked up at and the index it ended up at, if an item
+/// was dropped at a different position in this frame, e.g. to save the new order.
+/// Use [dnd] if you need a separate handle or any of the options of [Dnd].
+/// ```rust,ignore
+/// if let Some((from, to)) = egui_dnd::reorder(ui, "items", &mut items, |ui, item| {
+///     ui.label(*item);
+/// }) {
+///     println!("Moved item from {from} to {to}");
+/// }
+/// ```
+pub fn reorder<T: Hash>(
+    ui: &mut Ui,
+    id_source: impl Hash,
+    items: &mut [T],
+    mut item_ui: impl FnMut(&mut Ui, &mut T),
+) -> Option<(usize, usize)> {
+    let response = dnd(ui, id_source).show_vec(items, |ui, item, handle, _state| {
+        handle.ui(ui, |ui| item_ui(ui, item));
+    });
+    response.events().iter().find_map(|event| match *event {
+        DndEvent::Dropped { from, to } if from != to => Some((from, to)),
+        _ => None,
+    })
+}
+
 impl<'a> Dnd<'a> {
     /// Initialize the drag and drop UI. Same as [dnd].
     pub fn new(ui: &'a mut Ui, id_source: impl Hash) -> Self {
@@ -85,6 +133,603 @@ impl<'a> Dnd<'a> {
         self
     }
 
+    /// Automatically scroll the parent ScrollArea when the dragged item is near its edges.
+    /// The scroll speed ramps up the closer the pointer gets to the edge.
+    /// If not set, the ScrollArea is scrolled so the area around the pointer stays visible.
+    pub fn with_auto_scroll(mut self, config: AutoScrollConfig) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_auto_scroll(config);
+        self
+    }
+
+    /// Draw a line in the gap where the dragged item would be inserted if it was dropped now.
+    /// The line is horizontal in vertical lists and vertical in horizontal lists.
+    pub fn with_insertion_indicator(mut self, stroke: egui::Stroke) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_insertion_indicator(stroke);
+        self
+    }
+
+    /// Draw a border with the given stroke around the item the dragged item would be dropped onto,
+    /// i.e. the item it would be swapped with in [ReorderMode::Swap] or the [DropPosition::Into]
+    /// target with [Dnd::with_drop_into]. When the item would be inserted between two items in
+    /// [ReorderMode::Insert], the gap it would be inserted in is outlined instead, so the
+    /// highlight always shows where the item lands.
+    pub fn with_target_highlight(mut self, stroke: egui::Stroke) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_target_highlight(stroke);
+        self
+    }
+
+    /// Like [Dnd::with_insertion_indicator], but the stroke is picked for each gap, e.g. to draw
+    /// the line red in gaps the item can't be dropped in. `gap_indicator` is called with the
+    /// index of the gap and the index the dragged item would end up at. The index is None if
+    /// the item is dragged in from another list of the [Dnd::with_group].
+    /// If it returns None, no line is drawn in that gap.
+    pub fn with_gap_indicator(
+        mut self,
+        gap_indicator: impl Fn(usize, Option<usize>) -> Option<egui::Stroke> + 'a,
+    ) -> Self {
+        self.callbacks.gap_indicator = Some(Box::new(gap_indicator));
+        self
+    }
+
+    /// Sets whether the dragged item is inserted at the target position or swapped with the item
+    /// it is dropped onto. The default is [ReorderMode::Insert].
+    pub fn with_reorder_mode(mut self, mode: ReorderMode) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_reorder_mode(mode);
+        self
+    }
+
+    /// Sets how the position the dragged item would be dropped at is calculated.
+    /// By default the direction of the list is taken from the ui layout. Use [DndLayout::Vertical]
+    /// or [DndLayout::Horizontal] if the list is shown in a ui with a different main axis.
+    /// Use [DndLayout::Grid] for items in a `horizontal_wrapped` ui, so dragging to the start
+    /// of a row doesn't get confused by the row wrapping.
+    pub fn with_layout(mut self, layout: DndLayout) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_layout(layout);
+        self
+    }
+
+    /// Allow reordering items with the keyboard. While a handle has focus, space picks up the item,
+    /// the arrow keys move it by one position and space or enter drop it. Escape cancels the move.
+    /// The item only moves visually if the list is updated while dragging, e.g. with [Dnd::show_vec].
+    pub fn with_keyboard_reordering(mut self, enabled: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_keyboard_reordering(enabled);
+        self
+    }
+
+    /// By default, releasing the item outside of the list cancels the drag with
+    /// [CancellationReason::DroppedOutside] and moves the item back to where it was picked up.
+    /// If `clamp` is true, the item is dropped at the position closest to the pointer instead.
+    pub fn with_clamp_drop_outside(mut self, clamp: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_clamp_drop_outside(clamp);
+        self
+    }
+
+    /// Treat drags that are released before `duration` has passed as accidental: the item is
+    /// moved back and the drag is cancelled with [CancellationReason::TooShort]. This prevents
+    /// quick taps that moved the pointer a bit from reordering the list, in addition to
+    /// [Dnd::with_drag_threshold].
+    pub fn with_min_drag_time(mut self, duration: std::time::Duration) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_min_drag_time(duration);
+        self
+    }
+
+    /// Highlight the item after it was dropped at a new position, to confirm the move.
+    /// The item's background is tinted with `color`, which fades out over `duration`.
+    /// Drops that don't change the order and cancelled drags aren't highlighted.
+    pub fn with_drop_highlight(
+        mut self,
+        color: egui::Color32,
+        duration: std::time::Duration,
+    ) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drop_highlight(color, duration);
+        self
+    }
+
+    /// Let the user fling items: if the pointer is moving fast when the item is released, the
+    /// item travels further in that direction, the faster the further. This only works with
+    /// [ReorderMode::Insert]. Without it, the item is dropped exactly where it was released.
+    pub fn with_fling(mut self, enabled: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_fling(enabled);
+        self
+    }
+
+    /// Let items wrap around, e.g. for a circular playlist: dragging an item past the end of the
+    /// list moves it to the start, and dragging it before the start moves it to the end.
+    /// This only works with [ReorderMode::Insert] and isn't supported in [DndLayout::Grid].
+    pub fn with_wrap_around(mut self, wrap_around: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_wrap_around(wrap_around);
+        self
+    }
+
+    /// Let the whole item act as drag handle, so you don't need to call [Handle::ui].
+    /// Buttons and other clickable widgets in the item still work as long as the pointer is
+    /// released before it moved further than the drag threshold, see [Dnd::with_drag_threshold].
+    pub fn with_whole_item_as_handle(mut self, enabled: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_whole_item_as_handle(enabled);
+        self
+    }
+
+    /// Only start dragging when the given pointer button is pressed on a handle, e.g.
+    /// [egui::PointerButton::Middle]. Presses with the other buttons are left to the widgets in
+    /// the item, e.g. to open a context menu. By default, any button starts a drag.
+    pub fn with_drag_button(mut self, button: egui::PointerButton) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drag_button(button);
+        self
+    }
+
+    /// Give keyboard focus to the handle of an item after it was dropped, so pressing tab
+    /// continues from the moved item. This makes the handles focusable, like
+    /// [Dnd::with_keyboard_reordering] does.
+    pub fn with_focus_after_drop(mut self, enabled: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_focus_after_drop(enabled);
+        self
+    }
+
+    /// Sets the size of the area an empty list of a group takes up, so items of other lists can
+    /// be dropped into it. The [DragTransfer::to] index of such a drop is 0. By default the area
+    /// is as wide as the ui and as high as a button.
+    pub fn with_empty_drop_size(mut self, size: Vec2) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_empty_drop_size(size);
+        self
+    }
+
+    /// Allow dragging items between all lists that share the same `group_id`.
+    /// When an item is dropped into another list, that list's response contains a
+    /// [DragTransfer], use [DragDropResponse::transfer] to move the item between your lists:
+    /// ```rust,ignore
+    /// let backlog_response = dnd(ui, "backlog")
+    ///     .with_group("board")
+    ///     .show_vec(&mut backlog, item_ui);
+    /// let in_progress_response = dnd(ui, "in_progress")
+    ///     .with_group("board")
+    ///     .show_vec(&mut in_progress, item_ui);
+    ///
+    /// if let Some(transfer) = in_progress_response.transfer() {
+    ///     let item = backlog.remove(transfer.from);
+    ///     in_progress.insert(transfer.to, item);
+    /// }
+    /// ```
+    /// The list ids in [DragTransfer] are `Id::new(id_source)` of the id_source passed to [dnd],
+    /// so you can tell the lists apart if there are more than two.
+    /// Empty lists of a group take up some space, so items can still be dropped into them,
+    /// see [Dnd::with_empty_drop_size].
+    pub fn with_group(mut self, group_id: impl Hash) -> Self {
+        self.drag_drop_ui = self
+            .drag_drop_ui
+            .with_group(Id::new(group_id).with("dnd_group"), self.list_id);
+        self
+    }
+
+    /// Sets how far the pointer has to move from where it was pressed before a drag starts.
+    /// If the pointer is released before that, the press is handled as a normal click, so
+    /// buttons in the handle still work. See [DragDropConfig::click_tolerance].
+    /// This changes both the mouse and the touch config, so call it after
+    /// [Dnd::with_mouse_config] and [Dnd::with_touch_config].
+    pub fn with_drag_threshold(mut self, distance: f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drag_threshold(distance);
+        self
+    }
+
+    /// Like [Dnd::with_drag_threshold], but with a separate distance for each axis. The drag
+    /// starts once the pointer moved further than `threshold.x` horizontally or `threshold.y`
+    /// vertically, e.g. a larger vertical threshold for a horizontal list inside a vertical
+    /// ScrollArea, so vertical movements don't pick up items as quickly. The drag still starts
+    /// after [DragDropConfig::click_tolerance_timeout], like with [Dnd::with_drag_threshold].
+    pub fn with_drag_threshold_xy(mut self, threshold: Vec2) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drag_threshold_xy(threshold);
+        self
+    }
+
+    /// Stops the target from flickering between two positions when the dragged item is right at
+    /// the midpoint between them. Once a target is chosen, the item has to move `fraction` of its
+    /// size past the midpoint before the target changes, e.g. 0.1. The default is 0.0.
+    /// This doesn't apply to [DndLayout::Grid] and wrapping layouts.
+    pub fn with_reorder_hysteresis(mut self, fraction: f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_reorder_hysteresis(fraction);
+        self
+    }
+
+    /// Only start drags from the inner part of a handle, shrunk by `inset` on each side, e.g. so
+    /// pressing the margin of an item doesn't pick it up when the whole item is the handle.
+    /// The handle still takes up its full size.
+    pub fn with_hit_inset(mut self, inset: egui::Margin) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_hit_inset(inset);
+        self
+    }
+
+    /// If true, other widgets still report [egui::Response::hovered] while an item is dragged,
+    /// e.g. to highlight a folder the item is held over. By default egui treats the dragged
+    /// item like any other dragged widget and no other widget is hovered until it's dropped.
+    /// Releasing the pointer over another widget still doesn't click it.
+    pub fn with_pointer_passthrough(mut self, passthrough: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_pointer_passthrough(passthrough);
+        self
+    }
+
+    /// Sets the size of the gap that opens where the dragged item would be dropped. By default
+    /// the gap has the size the dragged item had in the last frame. Use this if the item would
+    /// have a different size at its target, e.g. because it's shown collapsed while dragged.
+    pub fn with_placeholder_size(mut self, size: Vec2) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_placeholder_size(size);
+        self
+    }
+
+    /// On touch devices, only start dragging after the finger has been held still for `delay`.
+    /// If the finger moves more than [DragDropConfig::scroll_tolerance] before that, it is treated
+    /// as a scroll gesture and no drag is started.
+    /// This is useful for lists in a ScrollArea, see [DragDropConfig::touch_scroll].
+    pub fn with_long_press_delay(mut self, delay: std::time::Duration) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_long_press_delay(delay);
+        self
+    }
+
+    /// Sets the easing function used when items move to their new position and when the
+    /// dropped item moves back into the list. By default, [simple_easing::cubic_in_out] is used
+    /// for the former and [simple_easing::cubic_out] for the latter.
+    /// Use [Dnd::with_settle_easing] and [Dnd::with_return_easing] to set them separately.
+    pub fn with_easing(mut self, easing: fn(f32) -> f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_easing(easing);
+        self
+    }
+
+    /// Sets the easing function used when items move to their new position while an item is
+    /// dragged. The default is [simple_easing::cubic_in_out].
+    pub fn with_settle_easing(mut self, easing: fn(f32) -> f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_settle_easing(easing);
+        self
+    }
+
+    /// Sets the easing function used when the dropped item moves into its place in the list,
+    /// and when a cancelled item moves back to where it was picked up. The default is
+    /// [simple_easing::cubic_out], use e.g. [simple_easing::back_out] for an overshoot.
+    pub fn with_return_easing(mut self, easing: fn(f32) -> f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_return_easing(easing);
+        self
+    }
+
+    /// Sets what happens when the pointer is pressed before the dropped item is back in the list.
+    /// By default, the items keep animating into place while the new drag starts, so the new
+    /// drag picks up the item where it's shown. If `snap` is true, the return animation is cut
+    /// short and the items jump to their positions in the list instead.
+    pub fn with_snap_on_interrupt(mut self, snap: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_snap_on_interrupt(snap);
+        self
+    }
+
+    /// Sets the duration of the item animations in seconds.
+    /// By default, `ui.style().animation_time` is used.
+    pub fn with_animation_time(mut self, seconds: f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_animation_time(seconds);
+        self
+    }
+
+    /// If false, items jump to their new position instead of being animated and a dropped item
+    /// is placed in the list immediately. This is useful for tests, where the animations would
+    /// make the layout depend on the frame timing.
+    pub fn with_animations(mut self, enabled: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_animations(enabled);
+        self
+    }
+
+    /// Set this to true if the user prefers reduced motion. Items then move to their new position
+    /// and the dropped item snaps back into the list without animation, while the dragged item still
+    /// follows the pointer. egui doesn't expose this preference, so it has to be passed in by the app.
+    pub fn with_reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_reduced_motion(reduced_motion);
+        self
+    }
+
+    /// Sets the opacity of the item while it is dragged, so the list below it stays visible.
+    /// The opacity is applied to the style colors of the dragged item's ui, so custom painted
+    /// shapes with hardcoded colors stay opaque. The item in the list is not affected.
+    pub fn with_drag_opacity(mut self, opacity: f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drag_opacity(opacity);
+        self
+    }
+
+    /// Scale the dragged item, e.g. by 1.05 to make it look lifted. The scale is animated in when
+    /// the drag starts and back out when the item is dropped. The space reserved for the item in
+    /// the list keeps the unscaled size.
+    /// Since egui can't transform the painted shapes, this scales the text sizes and spacing
+    /// of the item's style, so custom painted shapes with fixed sizes are not scaled.
+    pub fn with_drag_scale(mut self, scale: f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drag_scale(scale);
+        self
+    }
+
+    /// Paint a shadow below the dragged item, so it looks elevated. The shadow fades out while
+    /// the dropped item moves back into the list.
+    pub fn with_drag_shadow(mut self, shadow: egui::epaint::Shadow) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drag_shadow(shadow);
+        self
+    }
+
+    /// Sets the cursor shown while an item is dragged, the default is [egui::CursorIcon::Grabbing].
+    /// Pass None to set the cursor yourself, e.g. to show [egui::CursorIcon::NotAllowed] while
+    /// [DragDropResponse::is_hovering_remove_zone] returns true.
+    /// To change the cursor shown when hovering a handle, use [Handle::show_drag_cursor_on_hover].
+    pub fn with_dragging_cursor(mut self, cursor: Option<egui::CursorIcon>) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_dragging_cursor(cursor);
+        self
+    }
+
+    /// Sets the cursor shown while the pointer is over a handle and no item is dragged, the
+    /// default is [egui::CursorIcon::Grab]. Pass None to set the cursor yourself.
+    /// Once the drag starts, the cursor set with [Dnd::with_dragging_cursor] is shown instead.
+    pub fn with_hover_cursor(mut self, cursor: Option<egui::CursorIcon>) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_hover_cursor(cursor);
+        self
+    }
+
+    /// If false, the list never changes the cursor, neither while hovering a handle nor while
+    /// dragging, so you can manage it yourself in the item or handle contents, which are shown
+    /// after the cursor would be set. The default is true.
+    /// Unlike [Dnd::with_dragging_cursor] and [Dnd::with_hover_cursor], this keeps the
+    /// configured cursors, so they can be turned back on.
+    pub fn with_override_cursor(mut self, override_cursor: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_override_cursor(override_cursor);
+        self
+    }
+
+    /// Sets where the floating item is attached to the pointer while it's dragged. By default
+    /// it keeps the offset it had to the pointer when it was picked up, see [GrabAnchor].
+    pub fn with_grab_anchor(mut self, anchor: GrabAnchor) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_grab_anchor(anchor);
+        self
+    }
+
+    /// Sets the [egui::Order] of the layer the dragged item is shown on.
+    /// The default is [egui::Order::Foreground], use e.g. [egui::Order::Tooltip] to show it above
+    /// tooltips.
+    pub fn with_drag_layer(mut self, order: egui::Order) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drag_layer(order);
+        self
+    }
+
+    /// Clips the floating item to `rect`, e.g. the clip rect of the ScrollArea the list is in, so
+    /// the item doesn't draw outside of it while it's dragged near its edges.
+    /// ```rust,ignore
+    /// ScrollArea::vertical().show(ui, |ui| {
+    ///     let clip = ui.clip_rect();
+    ///     dnd(ui, "list").with_clip_floating(clip).show_vec(&mut items, item_ui);
+    /// });
+    /// ```
+    pub fn with_clip_floating(mut self, rect: Rect) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_clip_floating(rect);
+        self
+    }
+
+    /// The items with the given [DragDropItem::id]s can't be dragged and keep their index while
+    /// other items are reordered around them, e.g. for an "Add item" row at the end of the list.
+    /// Other items skip over them like over [Dnd::with_pinned_items], so they can still be moved
+    /// past them. Selected items dragged together with [Dnd::with_selection] can't be moved past
+    /// them, and in [ReorderMode::Swap] they can't be swapped with them.
+    /// Their handles are dimmed and show [egui::CursorIcon::NotAllowed] when hovered, see
+    /// [HandleState::draggable].
+    pub fn with_locked_items(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_locked_items(ids);
+        self
+    }
+
+    /// The items with the given [DragDropItem::id]s keep their index while other items are
+    /// reordered around them, e.g. for ads or pinned notices. Unlike [Dnd::with_locked_items],
+    /// other items can be moved past them and they can still be dragged themselves.
+    /// In [ReorderMode::Insert], [DragDropResponse::update_vec] skips over them, see
+    /// [utils::shift_vec_pinned]. If you apply [DragDropResponse::update] yourself, you have to
+    /// do the same. Selected items dragged together with [Dnd::with_selection] don't skip them.
+    /// While the list is updated during the drag, like [Dnd::show_vec] does, they also keep
+    /// their position on screen. In [Dnd::show_custom], pin items with
+    /// [ItemIterator::next_displaceable] instead.
+    pub fn with_pinned_items(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_pinned_items(ids);
+        self
+    }
+
+    /// Mark the items with the given [DragDropItem::id]s as dividers, e.g. section headers.
+    /// Dividers can't be dragged, but unlike [Dnd::with_locked_items] other items can be moved
+    /// past them, which moves them into another section. Since only the dragged item moves, the
+    /// dividers always stay in the same order.
+    /// Use [DragDropResponse::target_section] to get the section the item is dropped into.
+    pub fn with_dividers(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_dividers(ids);
+        self
+    }
+
+    /// Drag copies of the items instead of moving them, e.g. for a palette. The dragged item stays
+    /// in the list while a ghost follows the pointer and the gap shows where the copy would be
+    /// inserted. Use [DragDropResponse::copied] or [DragDropResponse::update_vec_copy] to
+    /// insert the copy when it's dropped; [DragDropResponse::update_vec] doesn't change the list.
+    /// The copy needs a different [DragDropItem::id] than the original.
+    /// This only works with [ReorderMode::Insert].
+    pub fn with_copy_on_drag(mut self, copy: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_copy_on_drag(copy);
+        self
+    }
+
+    /// If true, the dragged item can't leave the rect of the list, even if the pointer does.
+    /// The position the item would be dropped at is calculated from the constrained position.
+    pub fn with_constrain_to_rect(mut self, constrain: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_constrain_to_rect(constrain);
+        self
+    }
+
+    /// Sets the spacing between the items of the list, instead of `ui.spacing().item_spacing`.
+    /// The spacing is used for the static layout as well as for the gaps that open and close
+    /// while dragging, so they always match. The spacing of the ui after the list is unchanged.
+    pub fn with_spacing_override(mut self, spacing: Vec2) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_spacing_override(spacing);
+        self
+    }
+
+    /// Snaps the dragged item to a grid of `cell` sized cells, e.g. to align it with the grid
+    /// lines of a timeline. An axis of `cell` that is zero isn't snapped.
+    /// The cells start at the top left of the list, unless set with [Dnd::with_snap_origin].
+    /// The position the item would be dropped at is calculated from the snapped position, so
+    /// it matches what is shown.
+    pub fn with_snap(mut self, cell: Vec2) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_snap(cell);
+        self
+    }
+
+    /// Sets the position the cells of [Dnd::with_snap] start at.
+    pub fn with_snap_origin(mut self, origin: egui::Pos2) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_snap_origin(origin);
+        self
+    }
+
+    /// Sets the [DragDropItem::id]s of the selected items. When one of them is dragged, the other
+    /// selected items are dragged along, stacked below it, and the gap in the list makes room for
+    /// all of them. When they are dropped, they are moved next to each other, keeping their
+    /// relative order. Use [DragDropResponse::dragged_group] to get their indices.
+    /// The list isn't updated while the items are dragged, only when they are dropped.
+    /// This only works with [ReorderMode::Insert].
+    pub fn with_selection(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_selection(ids);
+        self
+    }
+
+    /// Allow dropping items onto other items, e.g. to nest them in a tree.
+    /// While the pointer is over the central `band` of an item (as a fraction of its height,
+    /// or width in a horizontal list), [DragDropResponse::drop_position] returns
+    /// [DropPosition::Into] and the list isn't reordered. E.g. with 0.5, the middle half of each
+    /// item drops into it and the outer quarters reorder as usual.
+    pub fn with_drop_into(mut self, band: f32) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_drop_into(band);
+        self
+    }
+
+    /// Register a rect, e.g. a trash can icon, that removes items dropped on it.
+    /// While the dragged item is over the zone it is tinted and
+    /// [DragDropResponse::is_hovering_remove_zone] returns true.
+    /// When it is dropped, [DragDropResponse::removed_index] returns its index and it's up to
+    /// you to remove it from the list, since [Dnd::show_vec] only has access to a slice.
+    pub fn with_remove_zone(mut self, rect: Rect) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_remove_zone(rect);
+        self
+    }
+
+    /// Only allow moving items to some positions. `filter` is called with the current index of the
+    /// dragged item and the index it would end up at (see [DragDropResponse::current_target_index])
+    /// and should return false if the item may not be moved there.
+    /// If the dragged item is over a rejected position, the gap moves to the closest accepted
+    /// position instead. While items are dragged together with [Dnd::with_selection] and in
+    /// [ReorderMode::Swap], the item stays at the last accepted position.
+    /// If it is dropped while the target is rejected anyway, e.g. because the filter changed,
+    /// the drag is cancelled with [CancellationReason::Rejected].
+    /// ```rust,ignore
+    /// // The header at index 0 has to stay at the top
+    /// dnd(ui, "list").with_drop_filter(|_from, to| to != 0)
+    /// ```
+    pub fn with_drop_filter(mut self, filter: impl Fn(usize, usize) -> bool + 'a) -> Self {
+        self.callbacks.drop_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Called every frame while dragging with the index of the item the pointer is over and how
+    /// long it has been over it, e.g. to open a folder when an item is held over it for a while.
+    /// This is purely informational and doesn't affect where the item is dropped.
+    pub fn with_hover_callback(
+        mut self,
+        on_hover: impl FnMut(usize, std::time::Duration) + 'a,
+    ) -> Self {
+        self.callbacks.on_hover = Some(Box::new(on_hover));
+        self
+    }
+
+    /// Called whenever the index the dragged item would end up at changes while dragging, with
+    /// the old and the new index, e.g. to play a sound for each slot the item passes.
+    /// The old index is None in the first frame of the drag.
+    pub fn with_target_changed_callback(
+        mut self,
+        on_target_changed: impl FnMut(Option<usize>, usize) + 'a,
+    ) -> Self {
+        self.callbacks.on_target_changed = Some(Box::new(on_target_changed));
+        self
+    }
+
+    /// Draw something in the gap the dragged item leaves in the list, e.g. a dashed outline.
+    /// The gap is where the item was picked up until it's moved to another position.
+    /// `placeholder` is called with a ui that covers the gap and its size, which is the size of
+    /// the dragged item once the gap is fully open. Without a placeholder, the gap stays empty.
+    pub fn with_source_placeholder(mut self, placeholder: impl FnMut(&mut Ui, Vec2) + 'a) -> Self {
+        self.callbacks.source_placeholder = Some(Box::new(placeholder));
+        self
+    }
+
+    /// Start dragging the item with the given [DragDropItem::id] in this frame, e.g. from a
+    /// keyboard shortcut or a button outside of the list.
+    /// `offset` is the position of the item's top left corner relative to the pointer,
+    /// so [Vec2::ZERO] attaches the item's top left corner to the pointer.
+    /// The drag ends when the pointer is released. If no pointer button is down, the item
+    /// follows the pointer until the next click.
+    pub fn start_drag(mut self, item_id: Id, offset: Vec2) -> Self {
+        self.drag_drop_ui.start_drag(item_id, offset);
+        self
+    }
+
+    /// Call this in the frame the [DragDropItem::id]s of the items changed, e.g. because a
+    /// background refresh of your data reassigned them. `remap` is called with the old id of an
+    /// item and returns its new id, so an active drag continues with the new id instead of
+    /// being lost. Return the id unchanged for items that kept their id.
+    /// All ids the list stores are remapped, including the ids passed to
+    /// [Dnd::with_selection], [Dnd::with_locked_items], [Dnd::with_pinned_items] and
+    /// [Dnd::with_dividers] in earlier frames, and the items keep animating to their positions.
+    /// See the id_remap example for a multi-select drag that continues while the ids change.
+    pub fn with_id_remap(mut self, remap: impl Fn(Id) -> Id) -> Self {
+        self.drag_drop_ui.remap_ids(remap);
+        self
+    }
+
+    /// Drag the item with the given [DragDropItem::id] to `to` and drop it there in this frame,
+    /// without a pointer, e.g. to test how your app handles a drop in a headless egui test.
+    /// `to` has the same meaning as [DragUpdate::to]. The returned response behaves as if the user
+    /// had dropped the item, so [DragDropResponse::update_vec] and [DragDropResponse::final_update]
+    /// work as usual. If the item isn't shown in this frame, nothing happens.
+    /// Only available with the `testing` feature.
+    /// ```rust,ignore
+    /// egui::__run_test_ui(|ui| {
+    ///     let mut items = vec!["a", "b", "c"];
+    ///     dnd(ui, "list")
+    ///         .simulate_drag(Id::new("a"), 3)
+    ///         .show_vec(&mut items, |ui, item, handle, _state| {
+    ///             handle.ui(ui, |ui| {
+    ///                 ui.label(*item);
+    ///             });
+    ///         });
+    ///     assert_eq!(items, ["b", "c", "a"]);
+    /// });
+    /// ```
+    #[cfg(feature = "testing")]
+    pub fn simulate_drag(mut self, item_id: Id, to: usize) -> Self {
+        self.drag_drop_ui.simulate_drag(item_id, to);
+        self
+    }
+
+    /// Cancel the current drag in this frame, e.g. because a modal dialog opened. The item moves
+    /// back to where it was picked up, like when escape is pressed, and the response reports
+    /// [CancellationReason::Programmatic]. Does nothing if no item is dragged.
+    pub fn cancel_drag(mut self) -> Self {
+        self.drag_drop_ui.cancel_drag();
+        self
+    }
+
+    /// If true, releasing the pointer doesn't drop the item. It stays where it was released
+    /// until [Dnd::commit_drag] or [Dnd::cancel_drag] is called, e.g. to let the user confirm
+    /// the new position with a button on imprecise touch screens.
+    /// Use [DragDropResponse::is_awaiting_commit] to know when to show the button.
+    /// This isn't supported for lists in a group, see [Dnd::with_group].
+    pub fn with_manual_commit(mut self, manual_commit: bool) -> Self {
+        self.drag_drop_ui = self.drag_drop_ui.with_manual_commit(manual_commit);
+        self
+    }
+
+    /// Drops the item that is waiting for a commit in this frame, see [Dnd::with_manual_commit].
+    /// Does nothing if no item is waiting.
+    pub fn commit_drag(mut self) -> Self {
+        self.drag_drop_ui.commit_drag();
+        self
+    }
+
     /// Display the drag and drop UI.
     /// `items` should be an iterator over items that should be sorted.
     ///
@@ -93,20 +738,24 @@ impl<'a> Dnd<'a> {
     ///
     /// `item_ui` is called for each item. Display your item there.
     /// `item_ui` gets a [Handle] that can be used to display the drag handle.
-    /// Only the handle can be used to drag the item. If you want the whole item to be draggable, put everything in the handle.
+    /// Only the handle can be used to drag the item. If you want the whole item to be draggable, put everything in the handle
+    /// or use [Dnd::with_whole_item_as_handle].
     pub fn show<T: DragDropItem>(
         self,
         items: impl Iterator<Item = T>,
         mut item_ui: impl FnMut(&mut Ui, T, Handle, ItemState),
     ) -> DragDropResponse {
-        self._show_with_inner(|_id, ui, drag_drop_ui| {
-            drag_drop_ui.ui(ui, |ui, iter| {
-                items.enumerate().for_each(|(i, item)| {
-                    iter.next(ui, item.id(), i, true, |ui, item_handle| {
-                        item_handle.ui(ui, |ui, handle, state| item_ui(ui, item, handle, state))
-                    });
+        self._show_with_inner(|ui, iter| {
+            if let (lower, Some(upper)) = items.size_hint() {
+                if lower == upper {
+                    iter.set_total(lower);
+                }
+            }
+            items.enumerate().for_each(|(i, item)| {
+                iter.next(ui, item.id(), i, true, |ui, item_handle| {
+                    item_handle.ui(ui, |ui, handle, state| item_ui(ui, item, handle, state))
                 });
-            })
+            });
         })
     }
 
@@ -121,20 +770,27 @@ impl<'a> Dnd<'a> {
         size: egui::Vec2,
         mut item_ui: impl FnMut(&mut Ui, T, Handle, ItemState),
     ) -> DragDropResponse {
-        self._show_with_inner(|_id, ui, drag_drop_ui| {
-            drag_drop_ui.ui(ui, |ui, iter| {
-                items.enumerate().for_each(|(i, item)| {
-                    iter.next(ui, item.id(), i, true, |ui, item_handle| {
-                        item_handle.ui_sized(ui, size, |ui, handle, state| {
-                            item_ui(ui, item, handle, state)
-                        })
-                    });
+        self._show_with_inner(|ui, iter| {
+            if let (lower, Some(upper)) = items.size_hint() {
+                if lower == upper {
+                    iter.set_total(lower);
+                }
+            }
+            items.enumerate().for_each(|(i, item)| {
+                iter.next(ui, item.id(), i, true, |ui, item_handle| {
+                    item_handle.ui_sized(ui, size, |ui, handle, state| {
+                        item_ui(ui, item, handle, state)
+                    })
                 });
-            })
+            });
         })
     }
 
     /// Same as [Dnd::show], but automatically sorts the items.
+    /// This is the easiest way to use egui_dnd if your items are in a Vec or slice: it shows each
+    /// item with `item_ui` and applies the [DragDropResponse::update_vec] for you, so the slice is
+    /// already in the new order when this returns.
+    /// Use [Dnd::show_custom] if you need more control over how the items are laid out.
     pub fn show_vec<T: Hash>(
         self,
         items: &mut [T],
@@ -145,6 +801,91 @@ impl<'a> Dnd<'a> {
         response
     }
 
+    /// Same as [Dnd::show_vec], but only the items in `visible` are shown, so very long lists stay
+    /// fast. Pass the range you get from `ScrollArea::show_rows`. The indices are the indices in
+    /// `items`, so the whole slice is sorted. The dragged item is shown even when it's scrolled
+    /// out of view, without taking up space, so the drag continues.
+    /// Use [Dnd::with_auto_scroll] to drag items to positions that aren't visible yet.
+    /// Since egui keeps the animation state of hidden items, they animate as usual when they
+    /// are scrolled back into view. See the virtual_list example.
+    pub fn show_vec_range<T: Hash>(
+        self,
+        items: &mut [T],
+        visible: Range<usize>,
+        mut item_ui: impl FnMut(&mut Ui, &mut T, Handle, ItemState),
+    ) -> DragDropResponse {
+        let dragged_item = self.drag_drop_ui.detection_state.dragged_item();
+        // A reversed range is treated as empty, so slicing can't panic
+        let end = visible.end.min(items.len());
+        let visible = visible.start.min(end)..end;
+        let response = self._show_with_inner(|ui, iter| {
+            iter.set_total(items.len());
+            let hidden_dragged_item = items
+                .iter_mut()
+                .enumerate()
+                .find(|(idx, item)| !visible.contains(idx) && Some(item.id()) == dragged_item);
+            if let Some((idx, item)) = hidden_dragged_item {
+                // The hidden item isn't part of the visible list, so it must not become its first
+                // or last item, which the gap behind the last item and wrap around rely on
+                let first_and_last = (iter.first_item, iter.last_item);
+                let mut child = ui.child_ui(ui.max_rect(), *ui.layout());
+                iter.next(&mut child, item.id(), idx, true, |ui, item_handle| {
+                    item_handle.ui(ui, |ui, handle, state| item_ui(ui, item, handle, state))
+                });
+                (iter.first_item, iter.last_item) = first_and_last;
+            }
+            let start = visible.start;
+            items[visible].iter_mut().enumerate().for_each(|(i, item)| {
+                iter.next(ui, item.id(), start + i, true, |ui, item_handle| {
+                    item_handle.ui(ui, |ui, handle, state| item_ui(ui, item, handle, state))
+                });
+            });
+        });
+        response.update_vec(items);
+        response
+    }
+
+    /// Same as [Dnd::show_vec], but the floating item is shown with `preview_ui` instead of
+    /// `item_ui`, e.g. to show a compact chip with just the title instead of the whole row.
+    /// If you prefer one closure, check [ItemState::floating] in `item_ui` instead.
+    pub fn show_vec_with_preview<T: Hash>(
+        self,
+        items: &mut [T],
+        mut item_ui: impl FnMut(&mut Ui, &mut T, Handle, ItemState),
+        mut preview_ui: impl FnMut(&mut Ui, &mut T, ItemState),
+    ) -> DragDropResponse {
+        self.show_vec(items, |ui, item, handle, state| {
+            if state.floating {
+                preview_ui(ui, item, state);
+            } else {
+                item_ui(ui, item, handle, state);
+            }
+        })
+    }
+
+    /// Same as [Dnd::show_vec], but the id of each item is derived from the key returned by `id`
+    /// instead of hashing the whole item. The key has to be unique and must not change while
+    /// the item is reordered, e.g. a database id. Don't use the index of the item, since it changes
+    /// when the item is moved and would break the animations.
+    /// Duplicate ids are reported by [DragDropResponse::has_duplicate_ids].
+    pub fn show_vec_with_id<T, K: Hash>(
+        self,
+        items: &mut [T],
+        id: impl Fn(&T) -> K,
+        mut item_ui: impl FnMut(&mut Ui, &mut T, Handle, ItemState),
+    ) -> DragDropResponse {
+        let response = self._show_with_inner(|ui, iter| {
+            iter.set_total(items.len());
+            items.iter_mut().enumerate().for_each(|(i, item)| {
+                iter.next(ui, Id::new(id(item)), i, true, |ui, item_handle| {
+                    item_handle.ui(ui, |ui, handle, state| item_ui(ui, item, handle, state))
+                });
+            });
+        });
+        response.update_vec(items);
+        response
+    }
+
     /// Same as [Dnd::show_sized], but automatically sorts the items.
     pub fn show_vec_sized<T: Hash>(
         self,
@@ -159,8 +900,11 @@ impl<'a> Dnd<'a> {
 
     /// This will allow for very flexible UI. You can use it to e.g. render outlines around items
     /// or render items in complex layouts. This is **experimental**.
+    /// You have to pass a unique id and the index of each item to `ItemIterator::next` yourself.
+    /// If you just want to show a slice, use [Dnd::show_vec] or [Dnd::show_vec_with_id] instead,
+    /// which iterate the items for you and pass each item with its [Handle] and [ItemState].
     pub fn show_custom(self, f: impl FnOnce(&mut Ui, &mut ItemIterator)) -> DragDropResponse {
-        self._show_with_inner(|_id, ui, drag_drop_ui| drag_drop_ui.ui(ui, f))
+        self._show_with_inner(f)
     }
 
     /// Same as [Dnd::show_custom], but automatically sorts the items.
@@ -174,17 +918,16 @@ impl<'a> Dnd<'a> {
         response
     }
 
-    fn _show_with_inner(
-        self,
-        inner_fn: impl FnOnce(Id, &mut Ui, &mut DragDropUi) -> DragDropResponse,
-    ) -> DragDropResponse {
+    fn _show_with_inner(self, f: impl FnOnce(&mut Ui, &mut ItemIterator)) -> DragDropResponse {
         let Dnd {
             id,
             ui,
             mut drag_drop_ui,
+            mut callbacks,
+            ..
         } = self;
 
-        let response = inner_fn(id, ui, &mut drag_drop_ui);
+        let response = drag_drop_ui.ui_with_callbacks(ui, &mut callbacks, f);
 
         ui.ctx().data_mut(|data| data.insert_temp(id, drag_drop_ui));
 
@@ -201,4 +944,27 @@ pub struct ItemState {
     /// of [Dnd::show_vec]), this index will updated while the item is being dragged.
     /// If you sort once after the item is dropped, the index will be stable during the drag.
     pub index: usize,
+    /// Number of items in the list, if it is known. This is the case for [Dnd::show_vec] and
+    /// for iterators that know their exact length. In [Dnd::show_custom], call `set_total` on
+    /// the item iterator before showing the items.
+    pub total: Option<usize>,
+    /// True if the item is shown floating above the list, i.e. while it's dragged and while it
+    /// moves back into the list after it was dropped. See [Dnd::show_vec_with_preview].
+    pub floating: bool,
+    /// False if the item keeps its position while other items are reordered around it, see
+    /// [Dnd::with_pinned_items] and [ItemIterator::next_displaceable].
+    pub displaceable: bool,
+}
+
+impl ItemState {
+    /// Returns true if this is the first item of the list, e.g. to round its top corners.
+    pub fn is_first(&self) -> bool {
+        self.index == 0
+    }
+
+    /// Returns true if this is the last item of the list. This is always false if the number of
+    /// items isn't known, see [ItemState::total].
+    pub fn is_last(&self) -> bool {
+        self.total.map_or(false, |total| self.index + 1 == total)
+    }
 }
@@ -0,0 +1,64 @@
+use egui::{Context, Id};
+
+/// Id the number of lists that are currently being shown is stored at
+fn depth_id() -> Id {
+    Id::new("egui_dnd_nesting_depth")
+}
+
+/// Id the [DragClaim] is stored at
+fn claim_id() -> Id {
+    Id::new("egui_dnd_drag_claim")
+}
+
+/// Called when a list starts showing its items, returns how many lists it is nested in plus one.
+pub(crate) fn enter_list(ctx: &Context) -> usize {
+    ctx.data_mut(|data| {
+        let depth = data.get_temp_mut_or_default::<usize>(depth_id());
+        *depth += 1;
+        *depth
+    })
+}
+
+/// Called when a list is done showing its items.
+pub(crate) fn leave_list(ctx: &Context) {
+    ctx.data_mut(|data| {
+        let depth = data.get_temp_mut_or_default::<usize>(depth_id());
+        *depth = depth.saturating_sub(1);
+    });
+}
+
+/// Written by a list when a drag starts.
+/// If the handle of a nested list is inside the item (or the handle) of the outer list, both lists
+/// would start dragging when the pointer is pressed. The innermost list wins.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DragClaim {
+    list_id: Id,
+    depth: usize,
+    frame_nr: u64,
+}
+
+impl DragClaim {
+    fn load(ctx: &Context) -> Option<Self> {
+        ctx.data_mut(|data| data.get_temp::<DragClaim>(claim_id()))
+            .filter(|claim| claim.frame_nr == ctx.frame_nr())
+    }
+
+    /// Returns true if a list nested deeper than `depth` started a drag in this frame.
+    pub(crate) fn claimed_by_nested_list(ctx: &Context, list_id: Id, depth: usize) -> bool {
+        Self::load(ctx).map_or(false, |claim| {
+            claim.list_id != list_id && claim.depth > depth
+        })
+    }
+
+    /// Claims the drag for the list, unless a list nested deeper already claimed it.
+    pub(crate) fn claim(ctx: &Context, list_id: Id, depth: usize) {
+        if !Self::claimed_by_nested_list(ctx, list_id, depth) {
+            let claim = DragClaim {
+                list_id,
+                depth,
+                frame_nr: ctx.frame_nr(),
+            };
+            ctx.data_mut(|data| data.insert_temp(claim_id(), claim));
+        }
+    }
+}
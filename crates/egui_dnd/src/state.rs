@@ -1,14 +1,22 @@
+use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{Duration, SystemTime};
 
-use egui::{CursorIcon, Id, Pos2, Rect, Sense, Ui, Vec2};
+use egui::epaint::Shadow;
+use egui::{
+    Color32, CursorIcon, Id, Key, Layout, Margin, Modifiers, Order, PointerButton, Pos2, Rect,
+    Sense, Stroke, Ui, Vec2, WidgetInfo, WidgetType,
+};
+use egui_animation::animate_position;
 
 #[cfg(target_arch = "wasm32")]
 use web_time::{Duration, SystemTime};
 
+use crate::group::{DndGroup, DndGroupState, DragTransfer, ForeignDrag};
 use crate::item_iterator::ItemIterator;
-use crate::utils::shift_vec;
+use crate::nesting::{self, DragClaim};
+use crate::utils::{pinned_landing_index, shift_vec_group, shift_vec_pinned};
 
 /// Item that can be reordered using drag and drop
 pub trait DragDropItem {
@@ -24,7 +32,7 @@ impl<T: Hash> DragDropItem for T {
 
 /// An instruction in what order to update the source list.
 /// The item at from should be removed from the list and inserted at to.
-/// You can use [shift_vec] to do this for a Vec.
+/// You can use [crate::utils::shift_vec] to do this for a Vec.
 #[derive(Debug, Clone)]
 pub struct DragUpdate {
     /// Index of the item to move
@@ -33,6 +41,168 @@ pub struct DragUpdate {
     pub to: usize,
 }
 
+/// Defines what happens to the other items when an item is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReorderMode {
+    /// The dragged item is inserted at the target position and the items in between are shifted.
+    #[default]
+    Insert,
+    /// The dragged item swaps places with the item it is dropped onto.
+    /// The list is only updated once the item is dropped.
+    Swap,
+}
+
+/// How the target position of the dragged item is calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DndLayout {
+    /// The target is the item closest to the dragged item, based on the layout of the ui.
+    #[default]
+    Auto,
+    /// The items are in a single column, only the y coordinate is used to find the target.
+    /// Use this if the list direction differs from the layout of the ui.
+    Vertical,
+    /// The items are in a single row, only the x coordinate is used to find the target.
+    /// Moving the pointer above or below the row doesn't change the target, even if the ui wraps.
+    /// This can also be used to reorder the columns of a table, see the table_columns example.
+    Horizontal,
+    /// The items are shown in a grid with a fixed number of columns and the same size,
+    /// e.g. in a `horizontal_wrapped` ui with [crate::Dnd::show_sized].
+    /// The target is the cell under the center of the dragged item.
+    Grid {
+        /// Number of items per row
+        columns: usize,
+    },
+}
+
+/// Where the floating item is attached to the pointer while it's dragged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrabAnchor {
+    /// The item keeps the offset to the pointer it had when it was picked up.
+    #[default]
+    Pointer,
+    /// The center of the item follows the pointer, e.g. for small handles on large items.
+    Center,
+    /// The top left corner of the item follows the pointer.
+    TopLeft,
+}
+
+impl DndLayout {
+    /// Returns whether the items are compared by their x coordinate
+    pub(crate) fn is_horizontal(self, ui_layout: &Layout) -> bool {
+        match self {
+            DndLayout::Vertical => false,
+            DndLayout::Horizontal => true,
+            DndLayout::Auto | DndLayout::Grid { .. } => ui_layout.is_horizontal(),
+        }
+    }
+}
+
+/// The reason why a drag & drop event was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancellationReason {
+    /// The pointer moved more than [DragDropConfig::scroll_tolerance] before the drag started.
+    ScrollToleranceExceeded,
+    /// The pointer was pressed outside of any item handle.
+    NotHoveringHandle,
+    /// The drag could have started but the pointer was not over any target.
+    NotHoveringTarget,
+    /// Escape was pressed while dragging with the pointer or the keyboard.
+    /// The item is returned to its original position.
+    Escape,
+    /// The item was dropped at a position rejected by [crate::Dnd::with_drop_filter].
+    /// The item is returned to its original position.
+    Rejected,
+    /// The pointer was released outside of the list. The item is returned to its original
+    /// position, unless [crate::Dnd::with_clamp_drop_outside] is set.
+    DroppedOutside,
+    /// The item was dropped before [crate::Dnd::with_min_drag_time] elapsed.
+    /// The item is returned to its original position.
+    TooShort,
+    /// The pressed handle is also part of a list nested in this list, so the item of the
+    /// nested list is dragged instead.
+    NestedListDragged,
+    /// The drag was cancelled with [crate::Dnd::cancel_drag].
+    /// The item is returned to its original position.
+    Programmatic,
+    /// The app lost focus while dragging, e.g. because the user switched to another window.
+    /// The item is returned to its original position.
+    FocusLost,
+}
+
+impl Display for CancellationReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            CancellationReason::ScrollToleranceExceeded => {
+                "Drag distance exceeded scroll threshold"
+            }
+            CancellationReason::NotHoveringHandle => "Cursor not hovering over any item handle",
+            CancellationReason::NotHoveringTarget => "Not hovering over any target",
+            CancellationReason::Escape => "Escape was pressed",
+            CancellationReason::Rejected => "The drop position was rejected",
+            CancellationReason::DroppedOutside => "The item was dropped outside of the list",
+            CancellationReason::TooShort => "The item was dropped too quickly",
+            CancellationReason::NestedListDragged => "An item of a nested list is dragged instead",
+            CancellationReason::Programmatic => "The drag was cancelled by the app",
+            CancellationReason::FocusLost => "The app lost focus",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// Where the dragged item would be dropped, see [DragDropResponse::drop_position].
+/// The indices are the current indices of the items in the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPosition {
+    /// The item would be inserted before the item at this index
+    Before(usize),
+    /// The item would be inserted after the item at this index
+    After(usize),
+    /// The item would be dropped onto the item at this index, e.g. to make it a child of it.
+    /// Only reported if [crate::Dnd::with_drop_into] is set.
+    Into(usize),
+}
+
+/// How the app should apply a drop, see [DragDropResponse::drop_kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropKind {
+    /// The item should be moved
+    Move,
+    /// The copy modifier ([Modifiers::command], Ctrl or Cmd on mac) was held when the item was
+    /// dropped, so a copy of the item should be inserted
+    Copy,
+}
+
+/// Something that happened to the list in a frame, see [DragDropResponse::events].
+/// Unlike [DragUpdate::to], the `to` indices are the index the item ends up at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DndEvent {
+    /// An item was picked up
+    Started {
+        /// Id of the dragged item
+        id: Id,
+    },
+    /// The dragged item moved to another position while the drag is ongoing.
+    /// This is only reported if the list is updated while dragging, e.g. with [crate::Dnd::show_vec].
+    Moved {
+        /// Current index of the dragged item
+        from: usize,
+        /// Index the item is moved to
+        to: usize,
+    },
+    /// The drag was cancelled
+    Cancelled {
+        /// Why the drag was cancelled
+        reason: CancellationReason,
+    },
+    /// The item was dropped. To undo the move, move the item from `to` back to `from`.
+    Dropped {
+        /// Index of the item when the drag started
+        from: usize,
+        /// Index the item was dropped at
+        to: usize,
+    },
+}
+
 /// Response containing state of the drag & drop list and a potential update to the source list.
 /// The update can be applied immediately or at latest when [DragDropResponse::is_drag_finished] returns true.
 #[derive(Debug, Clone)]
@@ -42,20 +212,78 @@ pub struct DragDropResponse {
     /// You can use this to consistently update the source list while the drag & drop event is ongoing.
     /// If you only want to update the source list when the drag & drop event has finished, use [DragDropResponse::final_update] instead.
     pub update: Option<DragUpdate>,
+    started: bool,
     finished: bool,
-    cancellation_reason: Option<&'static str>,
+    moved_item: Option<Id>,
+    cancellation_reason: Option<CancellationReason>,
     has_changed: bool,
+    reorder_mode: ReorderMode,
+    transfer: Option<DragTransfer>,
+    hovering_remove_zone: bool,
+    removed: Option<usize>,
+    drag_delta: Vec2,
+    events: Vec<DndEvent>,
+    drop_position: Option<DropPosition>,
+    target_section: Option<(usize, usize)>,
+    group: Option<Vec<usize>>,
+    item_ids: Vec<Id>,
+    copy: bool,
+    floating_pos: Option<Pos2>,
+    pinned: Vec<usize>,
+    hovering_handle: bool,
+    drop_pointer_pos: Option<Pos2>,
+    modifiers: Modifiers,
+    item_rects: Vec<(Id, Rect)>,
+    awaiting_commit: bool,
+    drag_session_id: Option<u64>,
+    duplicate_ids: bool,
 }
 
 impl DragDropResponse {
+    /// A response without an update, the fields are filled in by [DragDropUi::ui]
+    fn new(state: DragDetectionState, reorder_mode: ReorderMode) -> Self {
+        DragDropResponse {
+            state,
+            update: None,
+            started: false,
+            finished: false,
+            moved_item: None,
+            cancellation_reason: None,
+            has_changed: false,
+            reorder_mode,
+            transfer: None,
+            hovering_remove_zone: false,
+            removed: None,
+            drag_delta: Vec2::ZERO,
+            events: Vec::new(),
+            drop_position: None,
+            target_section: None,
+            group: None,
+            item_ids: Vec::new(),
+            copy: false,
+            floating_pos: None,
+            pinned: Vec::new(),
+            hovering_handle: false,
+            drop_pointer_pos: None,
+            modifiers: Modifiers::default(),
+            item_rects: Vec::new(),
+            awaiting_commit: false,
+            drag_session_id: None,
+            duplicate_ids: false,
+        }
+    }
+
     /// Returns true if we are currently evaluating whether a drag should be started.
     pub fn is_evaluating_drag(&self) -> bool {
         self.state.is_evaluating_drag()
     }
 
-    /// Returns true if we are currently dragging an item.
+    /// Returns true if we are currently dragging an item, either with the pointer or the keyboard,
+    /// e.g. to pause an animation in the background while the user reorders the list.
+    /// This is still true in the frame the item is dropped in, see
+    /// [DragDropResponse::is_drag_finished].
     pub fn is_dragging(&self) -> bool {
-        self.state.is_dragging()
+        self.state.is_dragging() || self.state.is_keyboard_dragging()
     }
 
     /// Returns the id of the item that is currently being dragged.
@@ -63,22 +291,231 @@ impl DragDropResponse {
         self.state.dragged_item()
     }
 
+    /// Returns the current index of the item that is being dragged.
+    pub fn dragged_index(&self) -> Option<usize> {
+        self.update.as_ref().map(|update| update.from)
+    }
+
+    /// Returns the index the dragged item would end up at if it was dropped right now.
+    /// Unlike [DragUpdate::to], this is the index after the item has been moved.
+    pub fn current_target_index(&self) -> Option<usize> {
+        self.update
+            .as_ref()
+            .map(|update| landing_index(self.reorder_mode, update.from, update.to))
+    }
+
+    /// Returns true if a drag was started in this frame.
+    pub fn is_drag_started(&self) -> bool {
+        self.started
+    }
+
+    /// Returns an id that is the same in all frames of one drag, from the frame it started in to
+    /// the frame it was dropped or cancelled in, e.g. to group the [DragDropResponse::events] of
+    /// a drag into one undo step. The id increases with every drag started in this list, so two
+    /// drags of the same item have different ids. Returns None if no item is dragged.
+    pub fn drag_session_id(&self) -> Option<u64> {
+        self.drag_session_id
+    }
+
+    /// Returns true if two items in the list had the same [crate::DragDropItem::id] in this
+    /// frame. Duplicate ids break the animations and dragging, use
+    /// [crate::Dnd::show_vec_with_id] to derive unique ids from a key.
+    /// In debug builds, a warning is painted over the list.
+    pub fn has_duplicate_ids(&self) -> bool {
+        self.duplicate_ids
+    }
+
+    /// Returns true if the pointer was released while dragging with
+    /// [crate::Dnd::with_manual_commit], so the item waits for [crate::Dnd::commit_drag] or
+    /// [crate::Dnd::cancel_drag], e.g. to show a confirm button.
+    pub fn is_awaiting_commit(&self) -> bool {
+        self.awaiting_commit
+    }
+
     /// Returns true if the drag & drop event has finished and the item has been dropped.
     /// The update should be applied to the source list.
     pub fn is_drag_finished(&self) -> bool {
         self.finished
     }
 
+    /// Returns true if the item was dropped in this frame at the position it was picked up at,
+    /// so the order of the list didn't change. This also works if the list was updated while
+    /// dragging, e.g. by [crate::Dnd::show_vec].
+    pub fn is_no_op(&self) -> bool {
+        self.events
+            .iter()
+            .any(|event| matches!(event, DndEvent::Dropped { from, to } if from == to))
+    }
+
+    /// Calls `f` with the index of the item when the drag started and the index it was dropped
+    /// at, once on the frame the item is dropped, and only if the order changed.
+    /// Useful to e.g. save the new order, without checking [DragDropResponse::is_drag_finished]
+    /// every frame.
+    pub fn on_drop(&self, f: impl FnOnce(usize, usize)) {
+        let dropped = self.events.iter().find_map(|event| match event {
+            DndEvent::Dropped { from, to } if from != to => Some((*from, *to)),
+            _ => None,
+        });
+        if let Some((from, to)) = dropped {
+            f(from, to);
+        }
+    }
+
+    /// Returns the id of the item that was dropped in this frame.
+    /// Use [DragDropResponse::final_update] to get the indices it was moved between.
+    pub fn moved_item_id(&self) -> Option<Id> {
+        self.moved_item
+    }
+
     /// Utility function to update a Vec with the current drag & drop state.
     /// You can use this to consistently update the source list while the drag & drop event is ongoing.
+    /// In [ReorderMode::Swap] the items are only swapped once the item has been dropped.
     pub fn update_vec<T>(&self, vec: &mut [T]) {
-        if self.has_changed || self.finished {
-            if let Some(update) = &self.update {
-                shift_vec(update.from, update.to, vec);
+        // The dragged item stays where it is, see [DragDropResponse::update_vec_copy]
+        if self.copy {
+            return;
+        }
+        match self.reorder_mode {
+            ReorderMode::Insert => {
+                if let Some(group) = &self.group {
+                    // The list isn't updated while a group is dragged
+                    if let (true, Some(update)) = (self.finished, &self.update) {
+                        shift_vec_group(group, update.to, vec);
+                    }
+                } else if self.has_changed || self.finished {
+                    if let Some(update) = &self.update {
+                        shift_vec_pinned(update.from, update.to, &self.pinned, vec);
+                    }
+                }
+            }
+            ReorderMode::Swap => {
+                if self.finished {
+                    if let Some(update) = &self.update {
+                        vec.swap(update.from, update.to);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the update if a copy of the dragged item was dropped in this frame, see
+    /// [crate::Dnd::with_copy_on_drag]. A copy of the item at [DragUpdate::from] should be
+    /// inserted at [DragUpdate::to]. The dragged item itself isn't moved.
+    pub fn copied(&self) -> Option<DragUpdate> {
+        if self.copy {
+            self.final_update()
+        } else {
+            None
+        }
+    }
+
+    /// Inserts a copy of the dropped item created by `clone` into the Vec, see
+    /// [DragDropResponse::copied]. The copy needs a different id than the original.
+    pub fn update_vec_copy<T>(&self, vec: &mut Vec<T>, clone: impl FnOnce(&T) -> T) {
+        if let Some(update) = self.copied() {
+            let copy = clone(&vec[update.from]);
+            vec.insert(update.to, copy);
+        }
+    }
+
+    /// Same as [DragDropResponse::update_vec], but for lists that are shown in reverse order,
+    /// e.g. a chat that grows upwards. The indices of the update are the positions the user
+    /// sees, so they are mirrored before the vec is updated.
+    /// ```rust,ignore
+    /// let response = dnd(ui, "messages").show(messages.iter().rev(), item_ui);
+    /// response.update_vec_rev(&mut messages);
+    /// ```
+    pub fn update_vec_rev<T>(&self, vec: &mut [T]) {
+        if self.update.is_none() {
+            return;
+        }
+        vec.reverse();
+        self.update_vec(vec);
+        vec.reverse();
+    }
+
+    /// Returns the ids of the items in the order they would have if the dragged item was dropped
+    /// now, e.g. to show a live preview of the new order. Returns None if no item is dragged.
+    pub fn preview_order(&self) -> Option<Vec<Id>> {
+        let mut ids = self.item_ids.clone();
+        self.apply_provisional(&mut ids).then_some(ids)
+    }
+
+    /// Moves the items of `vec` to the order they would have if the dragged item was dropped
+    /// now, e.g. to update numbered badges live while dragging.
+    /// `vec` has to be in the order the items were shown in this frame, so pass a scratch copy
+    /// of the list you passed to [crate::Dnd::show] before applying any update to it. Unlike
+    /// [DragDropResponse::update_vec], the update is applied in every frame of the drag and
+    /// in [ReorderMode::Swap] as well. Does nothing if no item is dragged.
+    /// ```rust,ignore
+    /// let response = dnd(ui, "items").show(items.iter(), item_ui);
+    /// let mut arrangement = items.clone();
+    /// response.provisional_update(&mut arrangement);
+    /// ```
+    pub fn provisional_update<T>(&self, vec: &mut [T]) {
+        self.apply_provisional(vec);
+    }
+
+    /// Returns false if there is no update that fits the list
+    fn apply_provisional<T>(&self, vec: &mut [T]) -> bool {
+        let Some(update) = &self.update else {
+            return false;
+        };
+        // The update may be from an earlier frame if the dragged item isn't shown anymore
+        let max_to = match self.reorder_mode {
+            ReorderMode::Insert => vec.len(),
+            ReorderMode::Swap => vec.len().saturating_sub(1),
+        };
+        if update.from >= vec.len() || update.to > max_to {
+            return false;
+        }
+        match (self.reorder_mode, &self.group) {
+            (ReorderMode::Insert, Some(group)) => shift_vec_group(group, update.to, vec),
+            (ReorderMode::Insert, None) => {
+                shift_vec_pinned(update.from, update.to, &self.pinned, vec)
             }
+            (ReorderMode::Swap, _) => vec.swap(update.from, update.to),
+        }
+        true
+    }
+
+    /// Returns the ids of the items in the order after the drop of this frame, or in the order
+    /// they were shown in if nothing was dropped. Use this instead of
+    /// [DragDropResponse::update_vec] if the items aren't stored in a slice, e.g. to reorder
+    /// the entries of a map. The items should only be reordered once the item is dropped.
+    pub fn ordered_ids(&self) -> Vec<Id> {
+        if self.finished && !self.copy {
+            self.preview_order()
+                .unwrap_or_else(|| self.item_ids.clone())
+        } else {
+            self.item_ids.clone()
         }
     }
 
+    /// Returns the ids of the items in the order they would have if the dragged item was dropped
+    /// now, or in the order they were shown in if no item is dragged.
+    /// See [DragDropResponse::preview_order].
+    pub fn provisional_ids(&self) -> Vec<Id> {
+        self.preview_order()
+            .unwrap_or_else(|| self.item_ids.clone())
+    }
+
+    /// Returns the rects of the items as they were laid out in this frame, in the order they
+    /// were shown. The rect of the dragged item is its place in the list, not where it floats.
+    /// Keep the response around to paint decorations like a minimap before the list is shown
+    /// in the next frame.
+    pub fn item_rects(&self) -> &[(Id, Rect)] {
+        &self.item_rects
+    }
+
+    /// Returns the rect of the item with the given id, see [DragDropResponse::item_rects].
+    pub fn item_rect(&self, id: Id) -> Option<Rect> {
+        self.item_rects
+            .iter()
+            .find(|(item_id, _)| *item_id == id)
+            .map(|(_, rect)| *rect)
+    }
+
     /// Returns the update if the drag & drop event has finished and the item has been dropped.
     /// Useful for the if let syntax.
     pub fn final_update(&self) -> Option<DragUpdate> {
@@ -89,28 +526,388 @@ impl DragDropResponse {
         }
     }
 
-    /// Returns a [Option<&str>] with the reason if a drag & drop event was cancelled.
-    pub fn cancellation_reason(&self) -> Option<&'static str> {
+    /// Returns the [CancellationReason] if a drag & drop event was cancelled.
+    pub fn cancellation_reason(&self) -> Option<CancellationReason> {
         self.cancellation_reason
     }
+
+    /// Returns the [DragTransfer] if an item from another list of the group (see
+    /// [crate::Dnd::with_group]) was dropped into this list.
+    /// This is only reported by the list the item was dropped into. The list it was dragged out
+    /// of reports [DragDropResponse::is_drag_finished] without an update.
+    pub fn transfer(&self) -> Option<&DragTransfer> {
+        self.transfer.as_ref()
+    }
+
+    /// Returns how far the pointer moved since the drag was started, including while the
+    /// drag was evaluated. On the frame the item is dropped, this is the movement from press
+    /// to release. Returns [Vec2::ZERO] if no item is dragged with the pointer.
+    pub fn drag_delta(&self) -> Vec2 {
+        self.drag_delta
+    }
+
+    /// Returns the distance between the position the pointer was pressed at and the current (or
+    /// release) position, see [DragDropResponse::drag_delta].
+    pub fn drag_distance(&self) -> f32 {
+        self.drag_delta.length()
+    }
+
+    /// Returns the position of the dragged item in the list when the drag started, e.g. to draw
+    /// an arrow to [DragDropResponse::floating_pos].
+    /// Returns None if no item is dragged with the pointer.
+    pub fn drag_origin(&self) -> Option<Pos2> {
+        match self.state {
+            DragDetectionState::Dragging { start_item_pos, .. } => Some(start_item_pos),
+            _ => None,
+        }
+    }
+
+    /// Returns the offset from the pointer to the top left corner of the floating item, e.g. to
+    /// show custom floating content in your own Area aligned like the item. The snapping of
+    /// [crate::Dnd::with_snap] isn't included, use [DragDropResponse::floating_pos] for that.
+    /// Returns None if no item is dragged with the pointer.
+    pub fn drag_offset(&self) -> Option<Vec2> {
+        match self.state {
+            DragDetectionState::Dragging { offset, .. } => Some(offset),
+            _ => None,
+        }
+    }
+
+    /// Returns the position of the floating dragged item, including the snapping of
+    /// [crate::Dnd::with_snap] and the constraint of [crate::Dnd::with_constrain_to_rect].
+    /// Returns None if no item is dragged with the pointer or the item is over another list of
+    /// the group.
+    pub fn floating_pos(&self) -> Option<Pos2> {
+        self.floating_pos
+    }
+
+    /// Returns where the dragged item would be dropped while dragging and where it was dropped
+    /// on the frame it was dropped.
+    /// If this is [DropPosition::Into], the list is not reordered and [DragDropResponse::update]
+    /// is None on the frame the item was dropped. It's up to you to move the item.
+    pub fn drop_position(&self) -> Option<DropPosition> {
+        self.drop_position
+    }
+
+    /// Returns where the pointer was released on the frame an item was dropped, e.g. to open a
+    /// context menu at the drop location. Not to be confused with
+    /// [DragDropResponse::drop_position], which is the position in the list.
+    pub fn drop_pointer_pos(&self) -> Option<Pos2> {
+        self.drop_pointer_pos
+    }
+
+    /// Returns the modifiers held while an item is dragged or on the frame it was dropped,
+    /// e.g. to show a copy indicator on the floating item. Empty if no item is dragged.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Returns whether the dropped item should be moved or copied, based on the modifiers held
+    /// when it was released. Only returns something on the frame the item was dropped.
+    /// egui_dnd doesn't copy anything itself, the app decides how to apply the drop.
+    pub fn drop_kind(&self) -> Option<DropKind> {
+        self.finished.then(|| {
+            if self.modifiers.command {
+                DropKind::Copy
+            } else {
+                DropKind::Move
+            }
+        })
+    }
+
+    /// Returns the section the dragged item would end up in and its index within that section,
+    /// while dragging and on the frame it was dropped. See [crate::Dnd::with_dividers].
+    /// Section 0 contains the items before the first divider, section 1 the items after the
+    /// first divider and so on. Without dividers, the section is always 0.
+    pub fn target_section(&self) -> Option<(usize, usize)> {
+        self.target_section
+    }
+
+    /// Returns the indices of the dragged items if multiple selected items are dragged together,
+    /// see [crate::Dnd::with_selection]. The indices are sorted and include the dragged item.
+    /// When the items are dropped, they should be moved to [DragUpdate::to] in this order,
+    /// [DragDropResponse::update_vec] and [crate::utils::shift_vec_group] do this for you.
+    pub fn dragged_group(&self) -> Option<&[usize]> {
+        self.group.as_deref()
+    }
+
+    /// Returns the [DndEvent]s of this frame, e.g. to record moves in an undo history.
+    pub fn events(&self) -> &[DndEvent] {
+        &self.events
+    }
+
+    fn collect_events(&self, start_idx: Option<usize>) -> Vec<DndEvent> {
+        let mut events = Vec::new();
+        if self.started {
+            if let Some(id) = self.state.dragged_item().or(self.moved_item) {
+                events.push(DndEvent::Started { id });
+            }
+        }
+        let update = self.update.as_ref().map(|update| {
+            (
+                update.from,
+                landing_index(self.reorder_mode, update.from, update.to),
+            )
+        });
+        if let Some(reason) = self.cancellation_reason {
+            events.push(DndEvent::Cancelled { reason });
+        } else if let (true, Some((from, to))) = (self.finished, update) {
+            events.push(DndEvent::Dropped {
+                from: start_idx.unwrap_or(from),
+                to,
+            });
+        } else if let (true, Some((from, to))) = (self.has_changed, update) {
+            if from != to {
+                events.push(DndEvent::Moved { from, to });
+            }
+        }
+        events
+    }
+
+    /// Returns true if the pointer is over the handle of any item in this frame, e.g. to
+    /// highlight the list to show that its items can be dragged.
+    pub fn is_hovering_handle(&self) -> bool {
+        self.hovering_handle
+    }
+
+    /// Returns true if the dragged item is over the remove zone, see [crate::Dnd::with_remove_zone].
+    pub fn is_hovering_remove_zone(&self) -> bool {
+        self.hovering_remove_zone
+    }
+
+    /// Returns the index of the item that was dropped on the remove zone in this frame.
+    /// The item should be removed from the source list.
+    pub fn removed_index(&self) -> Option<usize> {
+        self.removed
+    }
 }
 
 /// Holds the data needed to draw the floating item while it is being dragged
 /// Deprecated: Use [crate::dnd] or [crate::Dnd::new] instead
 #[derive(Clone, Debug)]
 pub struct DragDropUi {
+    /// Id of the list, set by [crate::dnd]. Used to tell nested lists apart.
+    pub(crate) id: Id,
+    /// Number of lists this list is nested in plus one, updated every frame
+    nesting_depth: usize,
     pub(crate) detection_state: DragDetectionState,
     /// If the mobile config is set, we will use it if we detect a touch event
     touch_config: Option<DragDropConfig>,
     mouse_config: DragDropConfig,
+    /// If set, the parent ScrollArea will be scrolled when dragging near its edges
+    auto_scroll: Option<AutoScrollConfig>,
+    /// If set, a line is drawn in the gap where the dragged item would be inserted
+    insertion_indicator: Option<Stroke>,
+    /// If set, a border is drawn around the item the dragged item would be dropped onto
+    target_highlight: Option<Stroke>,
+    pub(crate) reorder_mode: ReorderMode,
+    pub(crate) layout: DndLayout,
+    pub(crate) keyboard_reordering: bool,
+    /// If true, the handle of the dropped item gets keyboard focus
+    focus_after_drop: bool,
+    /// Set when an item was dropped with [DragDropUi::with_focus_after_drop], the handle
+    /// requests focus when it's shown
+    pending_focus: Option<Id>,
+    group: Option<DndGroup>,
+    /// Set while an item of another list in our group is dragged over this list
+    pub(crate) foreign_drag: Option<ForeignDrag>,
+    /// Used to check whether an item from another list is dragged over this list
+    last_list_rect: Rect,
+    /// Easing used when items move to their new position while dragging
+    pub(crate) settle_easing: fn(f32) -> f32,
+    /// Easing used when the dropped item moves back into the list
+    pub(crate) return_easing: fn(f32) -> f32,
+    /// If true, items snap into place when a drag starts during the return animation
+    snap_on_interrupt: bool,
+    /// The pointer was pressed while the dropped item moved back into the list, so the items
+    /// keep animating while we evaluate the new drag
+    pub(crate) interrupted_return: bool,
+    /// If None, `ui.style().animation_time` is used
+    animation_time: Option<f32>,
+    animations: bool,
+    /// Opacity of the floating item
+    pub(crate) drag_opacity: f32,
+    /// Fraction of an item's size in its center that drops the dragged item into it
+    pub(crate) drop_into: Option<f32>,
+    /// Scale of the floating item
+    pub(crate) drag_scale: f32,
+    /// Shadow painted below the floating item
+    pub(crate) drag_shadow: Option<Shadow>,
+    /// Cursor shown while an item is dragged
+    pub(crate) dragging_cursor: Option<CursorIcon>,
+    /// Cursor shown while a handle is hovered and no item is dragged
+    hover_cursor: Option<CursorIcon>,
+    /// If false, neither the dragging nor the hover cursor is set
+    override_cursor: bool,
+    /// Layer the floating item is shown on
+    pub(crate) drag_layer: Order,
+    /// The floating item is clipped to this rect
+    pub(crate) floating_clip: Option<Rect>,
+    /// Size of the area items can be dropped into while the list is empty
+    empty_drop_size: Option<Vec2>,
+    pub(crate) grab_anchor: GrabAnchor,
+    /// Overrides [DragDropConfig::click_tolerance] with a distance per axis
+    drag_threshold_xy: Option<Vec2>,
+    /// Size of the gap that opens where the dragged item would be dropped, instead of its size
+    pub(crate) placeholder_size: Option<Vec2>,
+    /// If true, the drag isn't registered with egui, so other widgets are still hovered
+    pointer_passthrough: bool,
+    /// Margin inside the rect of a handle that doesn't start a drag
+    hit_inset: Option<Margin>,
+    /// True once the dragged item was lifted out of the list, see [DragDropUi::is_lifted]
+    lifted: bool,
+    /// Fraction of the item size the item has to move past the midpoint to change the target
+    pub(crate) reorder_hysteresis: f32,
+    /// Items that can't be dragged or displaced by other items
+    locked_items: Vec<Id>,
+    /// Items that can't be dragged and divide the list into sections
+    dividers: Vec<Id>,
+    /// Items that keep their index while other items are moved past them
+    pinned_items: Vec<Id>,
+    /// True if the list had pinned items in the last frame, see [DragDropUi::has_pinned_items]
+    had_pinned_items: bool,
+    /// If true, the dragged item can't leave the list
+    constrain_to_rect: bool,
+    /// If set, used as `item_spacing` of the ui the items are shown in
+    spacing: Option<Vec2>,
+    /// Cell size the position of the dragged item is snapped to
+    snap: Option<Vec2>,
+    /// If None, the position is snapped relative to the top left of the list
+    snap_origin: Option<Pos2>,
+    /// If true, the dragged item stays in the list and a copy of it is dropped
+    pub(crate) copy_on_drag: bool,
+    /// Selected items, which are dragged together
+    selection: Vec<Id>,
+    /// The selected items that are currently dragged, including the dragged item
+    dragged_group: Vec<Id>,
+    /// Space taken up by the dragged group in the last frame, excluding the dragged item
+    pub(crate) dragged_group_extent: f32,
+    /// Items dropped on this rect are removed from the list
+    remove_zone: Option<Rect>,
+    pub(crate) hovering_remove_zone: bool,
+    /// The item the pointer is over while dragging and the time the pointer entered it
+    hovered_since: Option<(Id, f64)>,
+    /// Index the dragged item would end up at in the last frame, for
+    /// [crate::Dnd::with_target_changed_callback]
+    provisional_target: Option<usize>,
+    /// Number of drags started in this list, see [DragDropResponse::drag_session_id]
+    drag_session: u64,
+    /// Set by [DragDropUi::start_drag], the drag starts when the item's handle is shown
+    pending_drag: Option<(Id, Vec2)>,
+    /// Set by [DragDropUi::cancel_drag], reset at the end of the frame
+    cancel_requested: bool,
+    /// Whether the app was focused in the last frame, to detect when it loses focus
+    was_focused: bool,
+    /// If true, releasing the pointer doesn't drop the item, see [DragDropUi::commit_drag]
+    manual_commit: bool,
+    /// The pointer was released while dragging with [DragDropUi::with_manual_commit]
+    awaiting_commit: bool,
+    /// Set by [DragDropUi::commit_drag], reset at the end of the frame
+    commit_requested: bool,
+    /// Set by [DragDropUi::simulate_drag], the item id and [DragUpdate::to]
+    #[cfg(feature = "testing")]
+    simulated_drag: Option<(Id, usize)>,
+    reduced_motion: bool,
+    /// If None, any pointer button starts a drag
+    drag_button: Option<PointerButton>,
+    /// If true, the whole item acts as drag handle
+    pub(crate) whole_item_as_handle: bool,
+    /// If true, items dropped outside of the list are dropped at the closest position
+    clamp_drop_outside: bool,
+    /// If true, items released with a high speed travel further in that direction
+    fling: bool,
+    /// If true, dragging past one end of the list moves the item to the other end
+    wrap_around: bool,
+    /// Drags that are released earlier are cancelled
+    min_drag_time: Option<Duration>,
+    /// Color and duration in seconds of the highlight of a dropped item
+    drop_highlight: Option<(Color32, f32)>,
+    /// The item that was dropped last and `ui.input().time` when it was dropped
+    highlighted_item: Option<(Id, f64)>,
+    /// Ids of the items shown in the last frame, see [DragDropUi::remap_ids]
+    last_item_ids: Vec<Id>,
+    /// The old and the new id of the items remapped in this frame, so their animations continue
+    remapped_animations: Vec<(Id, Id)>,
 }
 
 impl Default for DragDropUi {
     fn default() -> Self {
         DragDropUi {
+            id: Id::null(),
+            nesting_depth: 0,
             detection_state: DragDetectionState::None,
             touch_config: Some(DragDropConfig::touch()),
             mouse_config: DragDropConfig::mouse(),
+            auto_scroll: None,
+            insertion_indicator: None,
+            target_highlight: None,
+            reorder_mode: ReorderMode::Insert,
+            layout: DndLayout::Auto,
+            keyboard_reordering: false,
+            focus_after_drop: false,
+            pending_focus: None,
+            group: None,
+            foreign_drag: None,
+            last_list_rect: Rect::NOTHING,
+            settle_easing: simple_easing::cubic_in_out,
+            return_easing: simple_easing::cubic_out,
+            snap_on_interrupt: false,
+            interrupted_return: false,
+            animation_time: None,
+            animations: true,
+            drag_opacity: 1.0,
+            drop_into: None,
+            drag_scale: 1.0,
+            drag_shadow: None,
+            dragging_cursor: Some(CursorIcon::Grabbing),
+            hover_cursor: Some(CursorIcon::Grab),
+            override_cursor: true,
+            drag_layer: Order::Foreground,
+            floating_clip: None,
+            empty_drop_size: None,
+            grab_anchor: GrabAnchor::Pointer,
+            drag_threshold_xy: None,
+            placeholder_size: None,
+            pointer_passthrough: false,
+            hit_inset: None,
+            lifted: false,
+            reorder_hysteresis: 0.0,
+            locked_items: Vec::new(),
+            dividers: Vec::new(),
+            pinned_items: Vec::new(),
+            had_pinned_items: false,
+            constrain_to_rect: false,
+            spacing: None,
+            snap: None,
+            snap_origin: None,
+            copy_on_drag: false,
+            selection: Vec::new(),
+            dragged_group: Vec::new(),
+            dragged_group_extent: 0.0,
+            remove_zone: None,
+            hovering_remove_zone: false,
+            hovered_since: None,
+            provisional_target: None,
+            drag_session: 0,
+            pending_drag: None,
+            cancel_requested: false,
+            was_focused: false,
+            manual_commit: false,
+            awaiting_commit: false,
+            commit_requested: false,
+            #[cfg(feature = "testing")]
+            simulated_drag: None,
+            reduced_motion: false,
+            drag_button: None,
+            whole_item_as_handle: false,
+            clamp_drop_outside: false,
+            fling: false,
+            wrap_around: false,
+            min_drag_time: None,
+            drop_highlight: None,
+            highlighted_item: None,
+            last_item_ids: Vec::new(),
+            remapped_animations: Vec::new(),
         }
     }
 }
@@ -119,6 +916,7 @@ impl Default for DragDropUi {
 pub struct Handle<'a> {
     id: Id,
     idx: usize,
+    total: Option<usize>,
     state: &'a mut DragDropUi,
     hovering_over_any_handle: &'a mut bool,
     item_pos: Pos2,
@@ -126,6 +924,35 @@ pub struct Handle<'a> {
     // Configurable options
     sense: Option<Sense>,
     show_drag_cursor_on_hover: bool,
+    accessibility_label: Option<String>,
+}
+
+/// The part of a [Handle::ui_with_grip] that starts a drag.
+#[derive(Debug, Default)]
+pub struct Grip {
+    rect: Option<Rect>,
+}
+
+impl Grip {
+    /// Show `contents` as the grip, only its rect starts a drag. Can be called more than once,
+    /// then all of the grips start a drag.
+    pub fn ui(&mut self, ui: &mut Ui, contents: impl FnOnce(&mut Ui)) -> Rect {
+        let rect = ui.scope(contents).response.rect;
+        self.rect = Some(self.rect.map_or(rect, |grip| grip.union(rect)));
+        rect
+    }
+}
+
+/// State of a [Handle], passed to the contents of [Handle::ui_with_state].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandleState {
+    /// True if the pointer is over the handle. This is based on the rect of the handle in the
+    /// last frame, since the contents are shown before the rect is known.
+    pub hovered: bool,
+    /// True if the item of the handle is being dragged.
+    pub dragged: bool,
+    /// False if the item can't be dragged, see [crate::Dnd::with_locked_items].
+    pub draggable: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -139,15 +966,25 @@ pub(crate) enum DragDetectionState {
         pressed_at: SystemTime,
     },
     CouldBeValidDrag,
-    Cancelled(&'static str),
+    Cancelled(CancellationReason),
     Dragging {
         id: Id,
         source_idx: usize,
+        /// Index of the item when the drag started, used to restore the position when cancelling
+        start_idx: usize,
         offset: Vec2,
         dragged_item_size: Vec2,
         closest_item: (Id, Pos2),
+        /// Position of the item in the list when the drag started
+        start_item_pos: Pos2,
         last_pointer_pos: Pos2,
+        /// Where the pointer was pressed to start the drag
+        start_pointer_pos: Pos2,
         hovering_last_item: bool,
+        /// Started with [DragDropUi::start_drag], so it continues while no pointer button is down
+        programmatic: bool,
+        /// `ui.input().time` when the drag started
+        started_at: f64,
 
         // These should only be used for output, as to not cause issues when item indexes change
         hovering_idx: usize,
@@ -156,10 +993,147 @@ pub(crate) enum DragDetectionState {
         id: Id,
         dragged_item_size: Option<Vec2>,
     },
+    /// The item was picked up with the keyboard and is moved with the arrow keys
+    KeyboardDragging {
+        id: Id,
+        source_idx: usize,
+        start_idx: usize,
+        target_idx: usize,
+    },
+}
+
+/// Release speed in points per second above which a drop is a fling, see [DragDropUi::with_fling]
+const FLING_SPEED: f32 = 1000.0;
+/// A flung item moves as far as the pointer would move at the release speed in this time
+const FLING_TIME: f32 = 0.15;
+
+/// Id of the focusable area of an item's handle, used for keyboard reordering
+pub(crate) fn keyboard_focus_id(item_id: Id) -> Id {
+    item_id.with("dnd_keyboard_handle")
+}
+
+/// Returns the section and the index within the section the item ends up in when it's moved
+/// from `from` to the landing index `to`, given the current indices of the dividers.
+fn section_position(
+    reorder_mode: ReorderMode,
+    from: usize,
+    to: usize,
+    divider_indices: &[usize],
+) -> (usize, usize) {
+    let (section, section_start) = divider_indices
+        .iter()
+        // The dividers between from and to move by one when the item is inserted
+        .map(|&idx| match reorder_mode {
+            ReorderMode::Insert if from < idx && idx <= to => idx - 1,
+            ReorderMode::Insert if to <= idx && idx < from => idx + 1,
+            _ => idx,
+        })
+        .filter(|&idx| idx < to)
+        .fold((0, 0), |(section, start), idx| {
+            (section + 1, usize::max(start, idx + 1))
+        });
+    (section, to - section_start)
+}
+
+/// Shows the text in the handle of an item that can't be dragged in the weak text color
+fn dim_if_not_draggable(ui: &mut Ui, draggable: bool) {
+    if !draggable {
+        ui.visuals_mut().override_text_color = Some(ui.visuals().weak_text_color());
+    }
+}
+
+/// Id the rect of an item's handle is stored at for [HandleState::hovered]
+fn handle_rect_id(item_id: Id) -> Id {
+    item_id.with("dnd_handle_rect")
+}
+
+/// Index the item ends up at when [DragUpdate] `from` -> `to` is applied
+fn landing_index(reorder_mode: ReorderMode, from: usize, to: usize) -> usize {
+    if reorder_mode == ReorderMode::Insert && to > from {
+        to - 1
+    } else {
+        to
+    }
+}
+
+/// Moves the gap in front of the item at `to` to where [shift_vec_pinned] actually puts the
+/// dragged item. Other items move past the pinned items, so the item can end up on the other
+/// side of a pinned item than the gap. Returns the item the gap is in front of, or the last
+/// item and true if the gap is behind it.
+/// `item_rects` are the rects of the shown items, the first of which has the index `first_idx`.
+/// Returns None if the rects don't line up with the indices, e.g. in [crate::Dnd::show_vec_range].
+fn pinned_gap(
+    (source_idx, source_id): (usize, Id),
+    to: usize,
+    pinned: &[usize],
+    item_rects: &[(Id, Rect)],
+    first_idx: usize,
+) -> Option<((usize, Id, Pos2), bool)> {
+    let len = item_rects.len();
+    let source_idx = source_idx.checked_sub(first_idx)?;
+    let to = to.checked_sub(first_idx)?;
+    if to > len || item_rects.get(source_idx).map(|(id, _)| *id) != Some(source_id) {
+        return None;
+    }
+    let pinned: Vec<usize> = pinned
+        .iter()
+        .filter_map(|idx| idx.checked_sub(first_idx))
+        .collect();
+    let landing = pinned_landing_index(source_idx, to, &pinned, len);
+    let to = if landing > source_idx {
+        landing + 1
+    } else {
+        landing
+    };
+    let (idx, last) = if to == len {
+        (len - 1, true)
+    } else {
+        (to, false)
+    };
+    let (id, rect) = item_rects[idx];
+    Some(((idx + first_idx, id, rect.min), last))
+}
+
+/// Returns the position closest to `to` that `accepts`, see [crate::Dnd::with_drop_filter].
+/// The position is returned like in [pinned_gap], as the item the gap is in front of, or the last
+/// item and true if the gap is behind it.
+/// Returns None if no position is accepted or the rects don't line up with the indices.
+fn nearest_accepted_gap(
+    (source_idx, source_id): (usize, Id),
+    to: usize,
+    reorder_mode: ReorderMode,
+    item_rects: &[(Id, Rect)],
+    first_idx: usize,
+    accepts: impl Fn(usize, usize) -> bool,
+) -> Option<((usize, Id, Pos2), bool)> {
+    let len = item_rects.len();
+    let candidates = first_idx..=first_idx + len;
+    let source = source_idx.checked_sub(first_idx)?;
+    if !candidates.contains(&to) || item_rects.get(source).map(|(id, _)| *id) != Some(source_id) {
+        return None;
+    }
+    // Search outwards from the rejected position, trying the position in front first
+    let target = (0..=len)
+        .flat_map(|distance| [to.checked_sub(distance), Some(to + distance)])
+        .flatten()
+        .filter(|candidate| candidates.contains(candidate))
+        .find(|candidate| {
+            accepts(
+                source_idx,
+                landing_index(reorder_mode, source_idx, *candidate),
+            )
+        })?;
+    let (idx, last) = if target - first_idx == len {
+        (len - 1, true)
+    } else {
+        (target - first_idx, false)
+    };
+    let (id, rect) = item_rects[idx];
+    Some(((idx + first_idx, id, rect.min), last))
 }
 
 impl DragDetectionState {
-    fn is_evaluating_drag(&self) -> bool {
+    pub(crate) fn is_evaluating_drag(&self) -> bool {
         matches!(self, DragDetectionState::WaitingForClickThreshold { .. })
             || matches!(self, DragDetectionState::PressedWaitingForDelay { .. })
             || matches!(self, DragDetectionState::CouldBeValidDrag)
@@ -169,9 +1143,14 @@ impl DragDetectionState {
         matches!(self, DragDetectionState::Dragging { .. })
     }
 
-    fn dragged_item(&self) -> Option<Id> {
+    pub(crate) fn is_keyboard_dragging(&self) -> bool {
+        matches!(self, DragDetectionState::KeyboardDragging { .. })
+    }
+
+    pub(crate) fn dragged_item(&self) -> Option<Id> {
         match self {
             DragDetectionState::Dragging { id, .. } => Some(*id),
+            DragDetectionState::KeyboardDragging { id, .. } => Some(*id),
             _ => None,
         }
     }
@@ -207,6 +1186,7 @@ impl<'a> Handle<'a> {
     pub(crate) fn new(
         id: Id,
         idx: usize,
+        total: Option<usize>,
         state: &'a mut DragDropUi,
         hovering_over_any_handle: &'a mut bool,
         item_pos: Pos2,
@@ -214,15 +1194,24 @@ impl<'a> Handle<'a> {
         Handle {
             id,
             idx,
+            total,
             state,
             hovering_over_any_handle,
             item_pos,
 
             sense: None,
             show_drag_cursor_on_hover: true,
+            accessibility_label: None,
         }
     }
 
+    /// Sets the name screen readers announce for the item, in front of its position and drag
+    /// state, e.g. "Buy milk, item 3 of 10, draggable".
+    pub fn accessibility_label(mut self, label: impl Into<String>) -> Self {
+        self.accessibility_label = Some(label.into());
+        self
+    }
+
     /// You can add [Sense::click] if you want to listen for clicks on the handle
     /// **Warning**: This will make anything sensing clicks in the handle not draggable
     /// Make sure to not set this if your handle consists of a single button, and directly
@@ -232,8 +1221,10 @@ impl<'a> Handle<'a> {
         self
     }
 
-    /// If `true`, the cursor will change to a grab cursor when hovering over the handle
-    /// This is `true` by default
+    /// If `true`, the cursor will change to a grab cursor when hovering over the handle, or to
+    /// [CursorIcon::NotAllowed] if the item can't be dragged.
+    /// This is `true` by default. To change the cursor for all handles, use
+    /// [crate::Dnd::with_hover_cursor]
     pub fn show_drag_cursor_on_hover(mut self, show: bool) -> Self {
         self.show_drag_cursor_on_hover = show;
         self
@@ -241,11 +1232,98 @@ impl<'a> Handle<'a> {
 
     /// Draw the drag handle. Use [Handle::sense] to add a click sense.
     /// You can also add buttons in the handle, but they won't be interactive if you pass Sense::click
+    /// Only the rect of `contents` starts a drag. To only drag from e.g. a grip icon, show just
+    /// the grip in the handle and the rest of the item next to it:
+    /// ```rust,ignore
+    /// ui.horizontal(|ui| {
+    ///     handle.ui(ui, |ui| {
+    ///         ui.label("☰");
+    ///     });
+    ///     ui.label(item);
+    /// });
+    /// ```
+    /// The contents of handles that can't start a drag are dimmed.
+    /// To mix the grip and the rest of the item in one layout, use [Handle::ui_with_grip].
     pub fn ui(mut self, ui: &mut Ui, contents: impl FnOnce(&mut Ui)) -> egui::Response {
-        let response = ui.scope(contents);
+        let draggable = self.is_draggable();
+        let response = ui.scope(|ui| {
+            dim_if_not_draggable(ui, draggable);
+            contents(ui);
+        });
         self.handle_response(response.response, ui)
     }
 
+    /// Same as [Handle::ui], but only the parts of `contents` shown with [Grip::ui] start a drag,
+    /// e.g. a grip icon. The rest of `contents` stays interactive and doesn't start a drag.
+    /// If no grip is shown, the whole contents start a drag like in [Handle::ui].
+    /// ```rust,ignore
+    /// handle.ui_with_grip(ui, |ui, grip| {
+    ///     ui.horizontal(|ui| {
+    ///         grip.ui(ui, |ui| {
+    ///             ui.label("☰");
+    ///         });
+    ///         ui.text_edit_singleline(&mut item.name);
+    ///     });
+    /// });
+    /// ```
+    pub fn ui_with_grip(
+        mut self,
+        ui: &mut Ui,
+        contents: impl FnOnce(&mut Ui, &mut Grip),
+    ) -> egui::Response {
+        let draggable = self.is_draggable();
+        let mut grip = Grip::default();
+        let response = ui.scope(|ui| {
+            dim_if_not_draggable(ui, draggable);
+            contents(ui, &mut grip);
+        });
+        let rect = grip.rect.unwrap_or(response.response.rect);
+        let response = ui.interact(rect, self.id.with("dnd_grip"), Sense::hover());
+        self.handle_response(response, ui)
+    }
+
+    /// Same as [Handle::ui], but `contents` also gets the [HandleState], e.g. to only show a
+    /// grip icon while the handle is hovered.
+    pub fn ui_with_state(
+        self,
+        ui: &mut Ui,
+        contents: impl FnOnce(&mut Ui, HandleState),
+    ) -> egui::Response {
+        let state = self.handle_state(ui);
+        let id = self.id;
+        let response = self.ui(ui, |ui| contents(ui, state));
+        ui.data_mut(|data| data.insert_temp(handle_rect_id(id), response.rect));
+        response
+    }
+
+    /// Same as [Handle::ui_sized], but `add_contents` also gets the [HandleState].
+    pub fn ui_sized_with_state(
+        self,
+        ui: &mut Ui,
+        size: Vec2,
+        add_contents: impl FnOnce(&mut Ui, HandleState),
+    ) -> egui::Response {
+        let state = self.handle_state(ui);
+        let id = self.id;
+        let response = self.ui_sized(ui, size, |ui| add_contents(ui, state));
+        ui.data_mut(|data| data.insert_temp(handle_rect_id(id), response.rect));
+        response
+    }
+
+    fn handle_state(&self, ui: &Ui) -> HandleState {
+        let last_rect = ui.data_mut(|data| data.get_temp::<Rect>(handle_rect_id(self.id)));
+        HandleState {
+            hovered: last_rect.map_or(false, |rect| ui.rect_contains_pointer(rect)),
+            dragged: self.state.is_lifted_item(self.id),
+            draggable: self.is_draggable(),
+        }
+    }
+
+    /// Locked items and dividers can't be dragged
+    fn is_draggable(&self) -> bool {
+        !self.state.is_locked(self.id) && !self.state.is_divider(self.id)
+    }
+
     /// This is useful if you want to sort items in a horizontal_wrapped.
     /// This doesn't create a new scope.
     pub fn ui_sized(
@@ -254,42 +1332,74 @@ impl<'a> Handle<'a> {
         size: Vec2,
         add_contents: impl FnOnce(&mut Ui),
     ) -> egui::Response {
+        let draggable = self.is_draggable();
         let response = ui.allocate_ui(size, |ui| {
             // We somehow have to push a new id here or there will be an id clash at response.interact
-            ui.push_id(self.id.with("handle"), add_contents)
+            ui.push_id(self.id.with("handle"), |ui| {
+                dim_if_not_draggable(ui, draggable);
+                add_contents(ui);
+            })
         });
         self.handle_response(response.response, ui)
     }
 
+    /// Uses the rect of the whole item as handle, see [DragDropUi::with_whole_item_as_handle].
+    pub(crate) fn ui_for_item_rect(mut self, ui: &mut Ui, rect: Rect) {
+        let response = ui.interact(rect, self.id.with("dnd_item_handle"), Sense::hover());
+        self.handle_response(response, ui);
+    }
+
     fn handle_response(&mut self, response: egui::Response, ui: &mut Ui) -> egui::Response {
         let response = if let Some(sense) = self.sense {
             response.interact(sense)
         } else {
             response
         };
+        // Only evaluated if egui needs it, e.g. for AccessKit
+        response.widget_info(|| WidgetInfo::labeled(WidgetType::Other, self.describe()));
+
+        // Locked items and dividers can't be dragged, so their handle behaves like any other widget
+        if !self.is_draggable() {
+            if let (true, true, Some(cursor)) = (
+                response.hovered(),
+                self.show_drag_cursor_on_hover,
+                self.state.cursor(Some(CursorIcon::NotAllowed)),
+            ) {
+                ui.output_mut(|o| o.cursor_icon = cursor);
+            }
+            return response;
+        }
+
+        if self.state.keyboard_reordering || self.state.focus_after_drop {
+            self.handle_keyboard(ui, response.rect);
+        }
 
-        if response.hovered() {
-            if self.show_drag_cursor_on_hover {
-                ui.output_mut(|o| o.cursor_icon = CursorIcon::Grab);
+        // Only the inner part of the handle starts a drag, see [DragDropUi::with_hit_inset]
+        let hit_rect = self.state.hit_rect(response.rect);
+        let hovered = response.hovered() && ui.rect_contains_pointer(hit_rect);
+
+        if hovered {
+            // While dragging, the dragging cursor is shown instead
+            if let (true, false, Some(cursor)) = (
+                self.show_drag_cursor_on_hover,
+                self.state.detection_state.is_dragging(),
+                self.state.cursor(self.state.hover_cursor),
+            ) {
+                ui.output_mut(|o| o.cursor_icon = cursor);
             }
             *self.hovering_over_any_handle = true;
         }
 
         let offset = self.item_pos.to_vec2() - response.hover_pos().unwrap_or_default().to_vec2();
 
-        let drag_distance = ui.input(|i| {
-            (i.pointer.hover_pos().unwrap_or_default()
-                - i.pointer.press_origin().unwrap_or_default())
-            .length()
+        let drag_delta = ui.input(|i| {
+            i.pointer.hover_pos().unwrap_or_default() - i.pointer.press_origin().unwrap_or_default()
         });
 
-        let click_threshold = 1.0;
-        let is_above_click_threshold = drag_distance > click_threshold;
+        let is_above_click_threshold = self.state.is_above_drag_threshold(ui, drag_delta);
 
-        if response.hovered()
-            && response
-                .rect
-                .contains(ui.input(|input| input.pointer.press_origin().unwrap_or_default()))
+        if hovered
+            && hit_rect.contains(ui.input(|input| input.pointer.press_origin().unwrap_or_default()))
         {
             if let DragDetectionState::WaitingForClickThreshold { pressed_at } =
                 self.state.detection_state
@@ -306,28 +1416,137 @@ impl<'a> Handle<'a> {
             }
         };
 
-        if response.hovered()
+        if hovered
             && matches!(
                 self.state.detection_state,
                 DragDetectionState::CouldBeValidDrag
             )
+            // The handle of a nested list inside of this handle was shown first and got the drag
+            && !DragClaim::claimed_by_nested_list(
+                ui.ctx(),
+                self.state.id,
+                self.state.nesting_depth,
+            )
         {
+            DragClaim::claim(ui.ctx(), self.state.id, self.state.nesting_depth);
             self.state.detection_state = DragDetectionState::Dragging {
                 id: self.id,
                 offset,
                 // We set this in the Item
                 dragged_item_size: Default::default(),
                 closest_item: (self.id, self.item_pos),
+                start_item_pos: self.item_pos,
                 source_idx: self.idx,
+                start_idx: self.idx,
                 hovering_idx: self.idx,
                 last_pointer_pos: response.hover_pos().unwrap_or_default(),
+                start_pointer_pos: ui
+                    .input(|i| i.pointer.press_origin())
+                    .or(response.hover_pos())
+                    .unwrap_or_default(),
                 hovering_last_item: false,
+                programmatic: false,
+                started_at: ui.input(|i| i.time),
             };
-            ui.memory_mut(|mem| mem.set_dragged_id(self.id));
+            // Otherwise the item is lifted in [DragDropUi::ui] once the pointer moves
+            if is_above_click_threshold {
+                self.state.lifted = true;
+            }
+        }
+
+        // egui doesn't report other widgets as hovered while something is dragged. We wait until
+        // the item is lifted, so the widgets in the handle still get the click if the drag was
+        // started by [DragDropConfig::click_tolerance_timeout] and the pointer didn't move.
+        if let DragDetectionState::Dragging {
+            id,
+            programmatic: false,
+            ..
+        } = self.state.detection_state
+        {
+            if id == self.id && self.state.lifted && !self.state.pointer_passthrough {
+                ui.memory_mut(|mem| mem.set_dragged_id(self.id));
+            }
+        }
+
+        if let Some((id, offset)) = self.state.pending_drag {
+            if id == self.id {
+                self.state.pending_drag = None;
+                let pointer_pos = ui.input(|i| i.pointer.hover_pos()).unwrap_or(self.item_pos);
+                self.state.detection_state = DragDetectionState::Dragging {
+                    id: self.id,
+                    offset,
+                    dragged_item_size: Default::default(),
+                    closest_item: (self.id, self.item_pos),
+                    start_item_pos: self.item_pos,
+                    source_idx: self.idx,
+                    start_idx: self.idx,
+                    hovering_idx: self.idx,
+                    last_pointer_pos: pointer_pos,
+                    start_pointer_pos: pointer_pos,
+                    hovering_last_item: false,
+                    programmatic: true,
+                    started_at: ui.input(|i| i.time),
+                };
+            }
         }
 
         response
     }
+
+    /// Describes the item for screen readers, see [Handle::accessibility_label]
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(label) = &self.accessibility_label {
+            parts.push(label.clone());
+        }
+        let item = if parts.is_empty() { "Item" } else { "item" };
+        parts.push(match self.total {
+            Some(total) => format!("{item} {} of {total}", self.idx + 1),
+            None => format!("{item} {}", self.idx + 1),
+        });
+        if self.state.detection_state.is_dragging_item(self.id) {
+            parts.push("dragging".to_owned());
+        } else if self.is_draggable() {
+            parts.push("draggable".to_owned());
+        }
+        parts.join(", ")
+    }
+
+    /// Makes the handle focusable and picks up the item when space is pressed while it has focus,
+    /// if keyboard reordering is enabled.
+    /// Moving and dropping the item is handled in [DragDropUi::ui].
+    fn handle_keyboard(&mut self, ui: &mut Ui, rect: Rect) {
+        let response = ui.interact(
+            rect,
+            keyboard_focus_id(self.id),
+            Sense::focusable_noninteractive(),
+        );
+
+        if self.state.pending_focus == Some(self.id) {
+            self.state.pending_focus = None;
+            response.request_focus();
+        }
+
+        if response.has_focus() {
+            ui.painter().rect_stroke(
+                rect,
+                ui.visuals().widgets.hovered.rounding,
+                ui.visuals().selection.stroke,
+            );
+
+            if self.state.keyboard_reordering
+                && matches!(self.state.detection_state, DragDetectionState::None)
+                && ui.input(|i| i.key_pressed(Key::Space))
+            {
+                self.state.detection_state = DragDetectionState::KeyboardDragging {
+                    id: self.id,
+                    source_idx: self.idx,
+                    start_idx: self.idx,
+                    target_idx: self.idx,
+                };
+            }
+        }
+    }
 }
 
 /// Configuration for drag detection.
@@ -386,22 +1605,715 @@ impl DragDropConfig {
     }
 }
 
-/// [DragDropUi] stores the state of the Drag & Drop list.
-impl DragDropUi {
-    /// Sets the config used when dragging with the mouse or when no touch config is set
-    pub fn with_mouse_config(mut self, config: DragDropConfig) -> Self {
-        self.mouse_config = config;
-        self
-    }
+/// Configuration for scrolling the parent ScrollArea while dragging near its edges.
+#[derive(Debug, Clone)]
+pub struct AutoScrollConfig {
+    /// Distance from the edge of the visible area at which scrolling starts.
+    pub margin: f32,
+    /// Scroll speed in points per second when the pointer is right at the edge.
+    /// The speed ramps up linearly while the pointer moves through the margin.
+    pub max_speed: f32,
+}
 
-    /// Sets the config used when dragging with touch
-    /// If None, the mouse config is used instead
-    /// Use [DragDropConfig::touch] or [DragDropConfig::touch_scroll] to get a config optimized for touch
-    /// The default is [DragDropConfig::touch]
-    /// For dragging in a ScrollArea, use [DragDropConfig::touch_scroll]
-    pub fn with_touch_config(mut self, config: Option<DragDropConfig>) -> Self {
-        self.touch_config = config;
-        self
+impl Default for AutoScrollConfig {
+    fn default() -> Self {
+        Self {
+            margin: 40.0,
+            max_speed: 800.0,
+        }
+    }
+}
+
+impl AutoScrollConfig {
+    /// Scrolls the parent ScrollArea if the pointer is within the margin of the clip rect.
+    /// We only scroll in a direction if the list continues beyond the clip rect there.
+    fn scroll(&self, ui: &Ui, pointer_pos: Pos2, list_rect: Rect) {
+        // We use the clip rect instead of the list rect so this works with nested scroll areas
+        let clip_rect = ui.clip_rect();
+        let margin = self.margin.max(f32::EPSILON);
+
+        // 0.0 at the inner border of the margin, 1.0 at (or beyond) the edge
+        let ramp = |distance_to_edge: f32| (1.0 - distance_to_edge / margin).clamp(0.0, 1.0);
+
+        let mut direction = Vec2::ZERO;
+        if list_rect.min.y < clip_rect.min.y {
+            direction.y += ramp(pointer_pos.y - clip_rect.min.y);
+        }
+        if list_rect.max.y > clip_rect.max.y {
+            direction.y -= ramp(clip_rect.max.y - pointer_pos.y);
+        }
+        if list_rect.min.x < clip_rect.min.x {
+            direction.x += ramp(pointer_pos.x - clip_rect.min.x);
+        }
+        if list_rect.max.x > clip_rect.max.x {
+            direction.x -= ramp(clip_rect.max.x - pointer_pos.x);
+        }
+
+        if direction != Vec2::ZERO {
+            let dt = ui.input(|i| i.stable_dt).min(0.1);
+            ui.scroll_with_delta(direction * self.max_speed * dt);
+            ui.ctx().request_repaint();
+        }
+    }
+}
+
+/// Closures set on [crate::Dnd]. They can't be part of [DragDropUi], since it is stored in egui memory.
+#[derive(Default)]
+pub(crate) struct DndCallbacks<'a> {
+    pub(crate) drop_filter: Option<Box<dyn Fn(usize, usize) -> bool + 'a>>,
+    pub(crate) on_hover: Option<Box<dyn FnMut(usize, Duration) + 'a>>,
+    pub(crate) on_target_changed: Option<Box<dyn FnMut(Option<usize>, usize) + 'a>>,
+    pub(crate) source_placeholder: Option<Box<dyn FnMut(&mut Ui, Vec2) + 'a>>,
+    pub(crate) gap_indicator: Option<Box<dyn Fn(usize, Option<usize>) -> Option<Stroke> + 'a>>,
+}
+
+/// [DragDropUi] stores the state of the Drag & Drop list.
+impl DragDropUi {
+    /// Sets the config used when dragging with the mouse or when no touch config is set
+    pub fn with_mouse_config(mut self, config: DragDropConfig) -> Self {
+        self.mouse_config = config;
+        self
+    }
+
+    /// Sets the config used when dragging with touch
+    /// If None, the mouse config is used instead
+    /// Use [DragDropConfig::touch] or [DragDropConfig::touch_scroll] to get a config optimized for touch
+    /// The default is [DragDropConfig::touch]
+    /// For dragging in a ScrollArea, use [DragDropConfig::touch_scroll]
+    pub fn with_touch_config(mut self, config: Option<DragDropConfig>) -> Self {
+        self.touch_config = config;
+        self
+    }
+
+    /// Scroll the parent ScrollArea when dragging near its edges, see [AutoScrollConfig].
+    pub fn with_auto_scroll(mut self, config: AutoScrollConfig) -> Self {
+        self.auto_scroll = Some(config);
+        self
+    }
+
+    /// Draw a line with the given stroke in the gap where the dragged item would be inserted.
+    pub fn with_insertion_indicator(mut self, stroke: Stroke) -> Self {
+        self.insertion_indicator = Some(stroke);
+        self
+    }
+
+    /// Draw a border with the given stroke around the item the dragged item would be dropped onto,
+    /// or around the gap it would be inserted in.
+    pub fn with_target_highlight(mut self, stroke: Stroke) -> Self {
+        self.target_highlight = Some(stroke);
+        self
+    }
+
+    /// Sets whether the dragged item is inserted or swapped with the target, see [ReorderMode].
+    pub fn with_reorder_mode(mut self, mode: ReorderMode) -> Self {
+        self.reorder_mode = mode;
+        self
+    }
+
+    /// Sets how the target position is calculated, see [DndLayout].
+    pub fn with_layout(mut self, layout: DndLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Allow picking up items with space, moving them with the arrow keys and dropping them
+    /// with space or enter while the handle has keyboard focus.
+    pub fn with_keyboard_reordering(mut self, enabled: bool) -> Self {
+        self.keyboard_reordering = enabled;
+        self
+    }
+
+    /// If true, items can be dragged by pressing anywhere on them, not just on the [Handle].
+    pub fn with_whole_item_as_handle(mut self, enabled: bool) -> Self {
+        self.whole_item_as_handle = enabled;
+        self
+    }
+
+    /// If true, items dropped outside of the list are moved to the closest position instead of
+    /// being cancelled with [CancellationReason::DroppedOutside].
+    pub fn with_clamp_drop_outside(mut self, clamp: bool) -> Self {
+        self.clamp_drop_outside = clamp;
+        self
+    }
+
+    /// If true, dragging an item past the end of the list moves it to the start and the other
+    /// way around.
+    pub fn with_wrap_around(mut self, wrap_around: bool) -> Self {
+        self.wrap_around = wrap_around;
+        self
+    }
+
+    /// If true, an item released while the pointer moves fast is moved further in that direction.
+    pub fn with_fling(mut self, enabled: bool) -> Self {
+        self.fling = enabled;
+        self
+    }
+
+    /// Drags that are released before `duration` has passed are cancelled with
+    /// [CancellationReason::TooShort].
+    pub fn with_min_drag_time(mut self, duration: Duration) -> Self {
+        self.min_drag_time = Some(duration);
+        self
+    }
+
+    /// Highlights the dropped item with `color`, fading out over `duration`.
+    pub fn with_drop_highlight(mut self, color: Color32, duration: Duration) -> Self {
+        self.drop_highlight = Some((color, duration.as_secs_f32()));
+        self
+    }
+
+    /// Returns the color of the highlight behind the item, if it was dropped recently.
+    pub(crate) fn drop_highlight_color(&self, ui: &Ui, id: Id) -> Option<Color32> {
+        let (color, duration) = self.drop_highlight?;
+        let (highlighted_id, dropped_at) = self.highlighted_item?;
+        let elapsed = (ui.input(|i| i.time) - dropped_at) as f32;
+        if highlighted_id != id || elapsed >= duration {
+            return None;
+        }
+        ui.ctx().request_repaint();
+        Some(color.gamma_multiply(1.0 - elapsed / duration))
+    }
+
+    /// Only start dragging when the given pointer button is pressed.
+    pub fn with_drag_button(mut self, button: PointerButton) -> Self {
+        self.drag_button = Some(button);
+        self
+    }
+
+    /// If true, the handle of an item gets keyboard focus after the item was dropped.
+    pub fn with_focus_after_drop(mut self, enabled: bool) -> Self {
+        self.focus_after_drop = enabled;
+        self
+    }
+
+    /// Sets [DragDropConfig::click_tolerance] for both the mouse and the touch config.
+    pub fn with_drag_threshold(mut self, distance: f32) -> Self {
+        self.mouse_config.click_tolerance = distance;
+        if let Some(touch_config) = &mut self.touch_config {
+            touch_config.click_tolerance = distance;
+        }
+        self
+    }
+
+    /// Sets a separate drag threshold for each axis, overriding [DragDropConfig::click_tolerance].
+    pub fn with_drag_threshold_xy(mut self, threshold: Vec2) -> Self {
+        self.drag_threshold_xy = Some(threshold);
+        self
+    }
+
+    /// Sets how far past the midpoint between two positions the dragged item has to move before
+    /// the target changes, as a fraction of its size.
+    pub fn with_reorder_hysteresis(mut self, fraction: f32) -> Self {
+        self.reorder_hysteresis = fraction.max(0.0);
+        self
+    }
+
+    /// Only starts drags from the rect of a handle shrunk by `inset`.
+    pub fn with_hit_inset(mut self, inset: Margin) -> Self {
+        self.hit_inset = Some(inset);
+        self
+    }
+
+    /// Returns true if the dragged item follows the pointer. Items of drags that were started by
+    /// [DragDropConfig::click_tolerance_timeout] stay in the list until the pointer moves.
+    pub(crate) fn is_lifted(&self) -> bool {
+        self.lifted
+    }
+
+    /// Returns true if the item with `id` is dragged and was lifted out of the list, see
+    /// [DragDropUi::is_lifted]. Items dragged with the keyboard are always lifted.
+    pub(crate) fn is_lifted_item(&self, id: Id) -> bool {
+        match self.detection_state {
+            DragDetectionState::Dragging { id: dragged, .. } => dragged == id && self.lifted,
+            DragDetectionState::KeyboardDragging { id: dragged, .. } => dragged == id,
+            _ => false,
+        }
+    }
+
+    /// Shrinks the rect of a handle by [DragDropUi::with_hit_inset]
+    pub(crate) fn hit_rect(&self, rect: Rect) -> Rect {
+        match self.hit_inset {
+            Some(inset) => Rect::from_min_max(
+                Pos2::new(rect.min.x + inset.left, rect.min.y + inset.top),
+                Pos2::new(rect.max.x - inset.right, rect.max.y - inset.bottom),
+            ),
+            None => rect,
+        }
+    }
+
+    /// If true, other widgets are still hovered while an item is dragged.
+    pub fn with_pointer_passthrough(mut self, passthrough: bool) -> Self {
+        self.pointer_passthrough = passthrough;
+        self
+    }
+
+    /// Sets the size of the gap that opens where the dragged item would be dropped.
+    pub fn with_placeholder_size(mut self, size: Vec2) -> Self {
+        self.placeholder_size = Some(size);
+        self
+    }
+
+    /// Only start a drag on touch devices after the finger was held still for `delay`.
+    /// Changes [DragDropConfig::drag_delay] of the touch config, creating one if there is none.
+    pub fn with_long_press_delay(mut self, delay: Duration) -> Self {
+        let touch_config = self.touch_config.get_or_insert_with(DragDropConfig::touch);
+        touch_config.drag_delay = delay;
+        if touch_config.scroll_tolerance.is_none() {
+            touch_config.scroll_tolerance = DragDropConfig::touch_scroll().scroll_tolerance;
+        }
+        self
+    }
+
+    /// Sets the easing used for all item animations.
+    pub fn with_easing(mut self, easing: fn(f32) -> f32) -> Self {
+        self.settle_easing = easing;
+        self.return_easing = easing;
+        self
+    }
+
+    /// Sets the easing used when items move to their new position while dragging.
+    pub fn with_settle_easing(mut self, easing: fn(f32) -> f32) -> Self {
+        self.settle_easing = easing;
+        self
+    }
+
+    /// Sets the easing used when the dropped or cancelled item moves back into the list.
+    pub fn with_return_easing(mut self, easing: fn(f32) -> f32) -> Self {
+        self.return_easing = easing;
+        self
+    }
+
+    /// Sets whether the items snap into place when a drag starts during the return animation.
+    pub fn with_snap_on_interrupt(mut self, snap: bool) -> Self {
+        self.snap_on_interrupt = snap;
+        self
+    }
+
+    /// Sets the duration of the item animations in seconds.
+    pub fn with_animation_time(mut self, seconds: f32) -> Self {
+        self.animation_time = Some(seconds);
+        self
+    }
+
+    /// If false, items jump to their new position instead of being animated.
+    pub fn with_animations(mut self, enabled: bool) -> Self {
+        self.animations = enabled;
+        self
+    }
+
+    /// If true, items jump to their new position like with [DragDropUi::with_animations] set to false.
+    pub fn with_reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.reduced_motion = reduced_motion;
+        self
+    }
+
+    /// Sets the opacity of the floating item, between 0.0 and 1.0.
+    pub fn with_drag_opacity(mut self, opacity: f32) -> Self {
+        self.drag_opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The items with the given ids can't be dragged and stay at their position.
+    pub fn with_locked_items(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.locked_items = ids.into_iter().collect();
+        self
+    }
+
+    pub(crate) fn is_locked(&self, id: Id) -> bool {
+        self.locked_items.contains(&id)
+    }
+
+    /// The items with the given ids keep their index when other items are moved past them.
+    pub fn with_pinned_items(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.pinned_items = ids.into_iter().collect();
+        self
+    }
+
+    pub(crate) fn is_pinned(&self, id: Id) -> bool {
+        self.pinned_items.contains(&id)
+    }
+
+    /// Returns true if the list has items that can't be displaced, either set with
+    /// [DragDropUi::with_pinned_items], [DragDropUi::with_locked_items] or in
+    /// [crate::ItemIterator::next_displaceable].
+    /// The gaps don't animate then, since a closing and an opening gap would move the pinned
+    /// items between them.
+    pub(crate) fn has_pinned_items(&self) -> bool {
+        !self.pinned_items.is_empty() || !self.locked_items.is_empty() || self.had_pinned_items
+    }
+
+    /// The items with the given ids can't be dragged and divide the list into sections.
+    pub fn with_dividers(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.dividers = ids.into_iter().collect();
+        self
+    }
+
+    pub(crate) fn is_divider(&self, id: Id) -> bool {
+        self.dividers.contains(&id)
+    }
+
+    /// If true, the dragged item is kept inside the rect of the list.
+    pub fn with_constrain_to_rect(mut self, constrain: bool) -> Self {
+        self.constrain_to_rect = constrain;
+        self
+    }
+
+    /// If true, the dragged item stays in the list and the drop reports where a copy of it should
+    /// be inserted.
+    pub fn with_copy_on_drag(mut self, copy: bool) -> Self {
+        self.copy_on_drag = copy;
+        self
+    }
+
+    /// Sets the spacing between the items, instead of the `item_spacing` of the ui.
+    pub fn with_spacing_override(mut self, spacing: Vec2) -> Self {
+        self.spacing = Some(spacing);
+        self
+    }
+
+    /// Snaps the position of the dragged item to multiples of `cell`. An axis of `cell` that
+    /// is zero isn't snapped.
+    pub fn with_snap(mut self, cell: Vec2) -> Self {
+        self.snap = Some(cell);
+        self
+    }
+
+    /// Sets the position the cells of [DragDropUi::with_snap] start at.
+    pub fn with_snap_origin(mut self, origin: Pos2) -> Self {
+        self.snap_origin = Some(origin);
+        self
+    }
+
+    /// Moves the position of the dragged item to the closest cell, if enabled with
+    /// [DragDropUi::with_snap], and so it stays inside the list, if enabled with
+    /// [DragDropUi::with_constrain_to_rect].
+    pub(crate) fn floating_item_pos(&self, pos: Pos2) -> Pos2 {
+        let list_rect = self.last_list_rect;
+        let pos = match self.snap {
+            Some(cell) => {
+                let origin = self.snap_origin.unwrap_or(if list_rect.is_positive() {
+                    list_rect.min
+                } else {
+                    Pos2::ZERO
+                });
+                let snap = |value: f32, origin: f32, cell: f32| {
+                    if cell > 0.0 {
+                        origin + ((value - origin) / cell).round() * cell
+                    } else {
+                        value
+                    }
+                };
+                Pos2::new(snap(pos.x, origin.x, cell.x), snap(pos.y, origin.y, cell.y))
+            }
+            None => pos,
+        };
+        match self.detection_state.dragged_item_size() {
+            Some(size) if self.constrain_to_rect && list_rect.is_positive() => {
+                pos.clamp(list_rect.min, (list_rect.max - size).max(list_rect.min))
+            }
+            _ => pos,
+        }
+    }
+
+    /// Sets the selected items. If one of them is dragged, the others are dragged along.
+    pub fn with_selection(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        self.selection = ids.into_iter().collect();
+        self
+    }
+
+    pub(crate) fn is_in_dragged_group(&self, id: Id) -> bool {
+        self.dragged_group.contains(&id)
+    }
+
+    /// Sets the scale of the dragged item, e.g. 1.05 to make it look lifted.
+    pub fn with_drag_scale(mut self, scale: f32) -> Self {
+        self.drag_scale = scale;
+        self
+    }
+
+    /// Paints a shadow below the dragged item.
+    pub fn with_drag_shadow(mut self, shadow: Shadow) -> Self {
+        self.drag_shadow = Some(shadow);
+        self
+    }
+
+    /// Sets the cursor shown while dragging. If None, the cursor isn't changed.
+    pub fn with_dragging_cursor(mut self, cursor: Option<CursorIcon>) -> Self {
+        self.dragging_cursor = cursor;
+        self
+    }
+
+    /// Sets the cursor shown while a handle is hovered. If None, the cursor isn't changed.
+    pub fn with_hover_cursor(mut self, cursor: Option<CursorIcon>) -> Self {
+        self.hover_cursor = cursor;
+        self
+    }
+
+    /// If false, the cursor is never changed, so it can be set in the item or handle contents.
+    pub fn with_override_cursor(mut self, override_cursor: bool) -> Self {
+        self.override_cursor = override_cursor;
+        self
+    }
+
+    /// Returns the cursor to show, or None if [DragDropUi::with_override_cursor] is false.
+    pub(crate) fn cursor(&self, cursor: Option<CursorIcon>) -> Option<CursorIcon> {
+        cursor.filter(|_| self.override_cursor)
+    }
+
+    /// Sets where the floating item is attached to the pointer.
+    pub fn with_grab_anchor(mut self, anchor: GrabAnchor) -> Self {
+        self.grab_anchor = anchor;
+        self
+    }
+
+    /// Clips the floating item to `rect`.
+    pub fn with_clip_floating(mut self, rect: Rect) -> Self {
+        self.floating_clip = Some(rect);
+        self
+    }
+
+    /// Sets the layer the dragged item is shown on.
+    pub fn with_drag_layer(mut self, order: Order) -> Self {
+        self.drag_layer = order;
+        self
+    }
+
+    /// Report [DropPosition::Into] when the pointer is over the central `band` of an item,
+    /// as a fraction of its size.
+    pub fn with_drop_into(mut self, band: f32) -> Self {
+        self.drop_into = Some(band.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Items that are dropped on `rect` are removed instead of reordered.
+    pub fn with_remove_zone(mut self, rect: Rect) -> Self {
+        self.remove_zone = Some(rect);
+        self
+    }
+
+    /// Starts dragging the item with the given id the next time its handle is shown.
+    /// `offset` is the position of the item's top left corner relative to the pointer.
+    /// The drag ends when the pointer is released, or when it is pressed and released if no
+    /// pointer button was down when the drag started.
+    pub fn start_drag(&mut self, id: Id, offset: Vec2) {
+        self.pending_drag = Some((id, offset));
+    }
+
+    /// Replaces the item ids stored in the state with `remap(id)`, for when the ids of the
+    /// items changed since the last frame. The position animations of the items continue with
+    /// the new ids.
+    pub fn remap_ids(&mut self, remap: impl Fn(Id) -> Id) {
+        match &mut self.detection_state {
+            DragDetectionState::Dragging {
+                id, closest_item, ..
+            } => {
+                *id = remap(*id);
+                closest_item.0 = remap(closest_item.0);
+            }
+            DragDetectionState::TransitioningBackAfterDragFinished { id, .. }
+            | DragDetectionState::KeyboardDragging { id, .. } => {
+                *id = remap(*id);
+            }
+            _ => {}
+        }
+        if let Some(foreign) = &mut self.foreign_drag {
+            foreign.source.item_id = remap(foreign.source.item_id);
+            foreign.closest_item = foreign.closest_item.map(|(id, pos)| (remap(id), pos));
+        }
+        if let Some((id, _)) = &mut self.pending_drag {
+            *id = remap(*id);
+        }
+        #[cfg(feature = "testing")]
+        if let Some((id, _)) = &mut self.simulated_drag {
+            *id = remap(*id);
+        }
+        self.pending_focus = self.pending_focus.map(&remap);
+        self.hovered_since = self.hovered_since.map(|(id, since)| (remap(id), since));
+        self.highlighted_item = self.highlighted_item.map(|(id, at)| (remap(id), at));
+        for ids in [
+            &mut self.selection,
+            &mut self.dragged_group,
+            &mut self.locked_items,
+            &mut self.dividers,
+            &mut self.pinned_items,
+        ] {
+            for id in ids.iter_mut() {
+                *id = remap(*id);
+            }
+        }
+        self.remapped_animations = self
+            .last_item_ids
+            .iter()
+            .map(|&old| (old, remap(old)))
+            .filter(|(old, new)| old != new)
+            .collect();
+    }
+
+    /// Makes the next frame respond as if the item with the given id was dragged and dropped at
+    /// `to`, which has the same meaning as [DragUpdate::to].
+    #[cfg(feature = "testing")]
+    pub fn simulate_drag(&mut self, id: Id, to: usize) {
+        self.simulated_drag = Some((id, to));
+    }
+
+    /// Cancels the current drag in this frame, like pressing escape. The item moves back to
+    /// where it was picked up and the response reports [CancellationReason::Programmatic].
+    pub fn cancel_drag(&mut self) {
+        self.cancel_requested = true;
+    }
+
+    /// If true, releasing the pointer leaves the item where it is until [DragDropUi::commit_drag]
+    /// or [DragDropUi::cancel_drag] is called. Not supported for lists in a group.
+    pub fn with_manual_commit(mut self, manual_commit: bool) -> Self {
+        self.manual_commit = manual_commit;
+        self
+    }
+
+    /// Drops the item that was released with [DragDropUi::with_manual_commit] in this frame.
+    pub fn commit_drag(&mut self) {
+        self.commit_requested = true;
+    }
+
+    /// Returns the pointer position the dragged item follows. The item stays where it was
+    /// released while it's waiting for [DragDropUi::commit_drag].
+    pub(crate) fn drag_pointer_pos(&self, ui: &Ui) -> Option<Pos2> {
+        if self.awaiting_commit {
+            self.detection_state.last_pointer_pos()
+        } else {
+            ui.input(|i| i.pointer.hover_pos())
+                .or_else(|| self.detection_state.last_pointer_pos())
+        }
+    }
+
+    /// Sets the size of the area items of other lists can be dropped into while the list is empty.
+    pub fn with_empty_drop_size(mut self, size: Vec2) -> Self {
+        self.empty_drop_size = Some(size);
+        self
+    }
+
+    /// Allow dragging items between all lists with the same group id.
+    pub(crate) fn with_group(mut self, group_id: Id, list_id: Id) -> Self {
+        self.group = Some(DndGroup { group_id, list_id });
+        self
+    }
+
+    fn animations_enabled(&self) -> bool {
+        self.animations && !self.reduced_motion
+    }
+
+    /// Animates the position of the item with the given id towards `target`,
+    /// or returns `target` if animations are disabled.
+    /// If `animation_time` is None, the animation time of the list is used.
+    pub(crate) fn animate_position(
+        &self,
+        ui: &mut Ui,
+        id: Id,
+        target: Pos2,
+        easing: fn(f32) -> f32,
+        scroll_correction: bool,
+        animation_time: Option<f32>,
+    ) -> Pos2 {
+        if self.animations_enabled() {
+            let time = animation_time.unwrap_or_else(|| self.animation_time_or_style(ui));
+            if let Some((old_id, _)) = self.remapped_animations.iter().find(|(_, new)| *new == id) {
+                // Start the animation of the new id where the one of the old id currently is
+                let current = animate_position(
+                    ui,
+                    self.animation_id(*old_id),
+                    target,
+                    time,
+                    easing,
+                    scroll_correction,
+                );
+                animate_position(
+                    ui,
+                    self.animation_id(id),
+                    current,
+                    0.0,
+                    easing,
+                    scroll_correction,
+                );
+            }
+            animate_position(
+                ui,
+                self.animation_id(id),
+                target,
+                time,
+                easing,
+                scroll_correction,
+            )
+        } else {
+            target
+        }
+    }
+
+    /// Namespaces the animations of an item, so an item shown in two lists, e.g. an outer and a
+    /// nested one, doesn't share its animations. Lists of a group share them, so an item moved
+    /// to another list keeps animating from where it was dropped.
+    fn animation_id(&self, item_id: Id) -> Id {
+        self.group
+            .map_or(self.id, |group| group.group_id)
+            .with(item_id)
+    }
+
+    fn animation_time_or_style(&self, ui: &Ui) -> f32 {
+        self.animation_time
+            .unwrap_or_else(|| ui.style().animation_time)
+    }
+
+    /// Returns 1.0 while the item with the given id is dragged and 0.0 once it's dropped,
+    /// animated in between. Used to animate the scale and shadow of the floating item.
+    pub(crate) fn lift_animation(&self, ui: &Ui, id: Id, lifted: bool) -> f32 {
+        self.animate_bool(ui, id.with("dnd_lift"), lifted)
+    }
+
+    /// Returns how far the gap for the dragged item in front of the item with the given id is
+    /// open, from 0.0 to 1.0.
+    pub(crate) fn gap_animation(&self, ui: &Ui, id: Id, open: bool) -> f32 {
+        if self.has_pinned_items() {
+            // The items still move to the gap smoothly, since their positions are animated
+            return if open { 1.0 } else { 0.0 };
+        }
+        self.animate_bool(ui, id.with("dnd_gap"), open)
+    }
+
+    fn animate_bool(&self, ui: &Ui, id: Id, value: bool) -> f32 {
+        if self.animations_enabled() {
+            ui.ctx().animate_bool_with_time(
+                self.animation_id(id),
+                value,
+                self.animation_time_or_style(ui),
+            )
+        } else if value {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns whether the button that drags items was pressed, is down and was released
+    fn drag_button_state(&self, ui: &Ui) -> (bool, bool, bool) {
+        ui.input(|i| match self.drag_button {
+            Some(button) => (
+                i.pointer.button_pressed(button),
+                i.pointer.button_down(button),
+                i.pointer.button_released(button),
+            ),
+            None => (
+                i.pointer.any_pressed(),
+                i.pointer.any_down(),
+                i.pointer.any_released(),
+            ),
+        })
+    }
+
+    /// Returns true if the pointer moved far enough from where it was pressed to start a drag,
+    /// see [DragDropConfig::click_tolerance] and [DragDropUi::with_drag_threshold_xy].
+    pub(crate) fn is_above_drag_threshold(&self, ui: &Ui, delta: Vec2) -> bool {
+        match self.drag_threshold_xy {
+            Some(threshold) => delta.x.abs() > threshold.x || delta.y.abs() > threshold.y,
+            None => delta.length() > self.config(ui).click_tolerance,
+        }
     }
 
     fn config(&self, ui: &Ui) -> &DragDropConfig {
@@ -418,19 +2330,39 @@ impl DragDropUi {
         ui: &mut Ui,
         callback: impl FnOnce(&mut Ui, &mut ItemIterator),
     ) -> DragDropResponse {
+        self.ui_with_callbacks(ui, &mut DndCallbacks::default(), callback)
+    }
+
+    /// Same as [DragDropUi::ui], but calls the [DndCallbacks] set on [crate::Dnd].
+    pub(crate) fn ui_with_callbacks(
+        &mut self,
+        ui: &mut Ui,
+        callbacks: &mut DndCallbacks,
+        callback: impl FnOnce(&mut Ui, &mut ItemIterator),
+    ) -> DragDropResponse {
+        let drop_filter = callbacks.drop_filter.as_deref();
         // During the first frame, we check if the pointer is actually over any of the item handles and cancel the drag if it isn't
         let mut first_frame = false;
         let config = self.config(ui).clone();
+        let (button_pressed, button_down, pointer_released) = self.drag_button_state(ui);
 
         ui.input(|i| {
-            if i.pointer.any_down() {
-                if matches!(self.detection_state, DragDetectionState::None)
-                    || matches!(
-                        self.detection_state,
-                        DragDetectionState::TransitioningBackAfterDragFinished { .. }
-                    )
+            if button_down {
+                // We only start evaluating on the frame the pointer is pressed, so a drag that
+                // was cancelled while the pointer is still down isn't picked up again
+                if button_pressed
+                    && (matches!(self.detection_state, DragDetectionState::None)
+                        || matches!(
+                            self.detection_state,
+                            DragDetectionState::TransitioningBackAfterDragFinished { .. }
+                        ))
                 {
                     first_frame = true;
+                    self.interrupted_return = !self.snap_on_interrupt
+                        && matches!(
+                            self.detection_state,
+                            DragDetectionState::TransitioningBackAfterDragFinished { .. }
+                        );
                     self.detection_state = DragDetectionState::PressedWaitingForDelay {
                         pressed_at: SystemTime::now(),
                     };
@@ -451,12 +2383,12 @@ impl DragDropUi {
                                 DragDetectionState::WaitingForClickThreshold { pressed_at };
                         } else {
                             self.detection_state = DragDetectionState::Cancelled(
-                                "Drag distance exceeded scroll threshold",
+                                CancellationReason::ScrollToleranceExceeded,
                             );
                         }
                     } else if !is_below_scroll_threshold {
                         self.detection_state = DragDetectionState::Cancelled(
-                            "Drag distance exceeded scroll threshold",
+                            CancellationReason::ScrollToleranceExceeded,
                         );
                     }
                 }
@@ -470,9 +2402,59 @@ impl DragDropUi {
             }
         });
 
-        let pointer_pos = ui
-            .input(|i| i.pointer.hover_pos())
-            .or_else(|| self.detection_state.last_pointer_pos());
+        let pointer_pos = self.drag_pointer_pos(ui);
+
+        // A drag can start without the pointer moving, after
+        // [DragDropConfig::click_tolerance_timeout]. Until the pointer moves, the item stays in
+        // the list, so releasing it still clicks the widgets in it.
+        if let (
+            DragDetectionState::Dragging {
+                start_pointer_pos,
+                programmatic,
+                ..
+            },
+            Some(pointer_pos),
+        ) = (&self.detection_state, pointer_pos)
+        {
+            if *programmatic
+                || config.drag_delay > Duration::ZERO
+                || self.is_above_drag_threshold(ui, pointer_pos - *start_pointer_pos)
+            {
+                self.lifted = true;
+            }
+        }
+
+        let group_state = self
+            .group
+            .map(|group| DndGroupState::load(ui.ctx(), group.group_id));
+
+        // True if the item we are dragging is over another list of our group
+        let mut handed_off = false;
+        if let (Some(group), Some(group_state)) = (self.group, &group_state) {
+            handed_off = self.detection_state.is_dragging()
+                && group_state
+                    .target_other_list(ui.ctx(), group.list_id)
+                    .is_some();
+
+            // On the frame the item is dropped the other list may already have cleared its
+            // state, so we keep what we had in the last frame
+            if !pointer_released {
+                let foreign = group_state
+                    .dragged_from_other_list(ui.ctx(), group.list_id)
+                    .filter(|_| {
+                        !self.detection_state.is_dragging()
+                            && pointer_pos.map_or(false, |pos| self.last_list_rect.contains(pos))
+                    });
+                self.foreign_drag = match (foreign, self.foreign_drag.take()) {
+                    (Some(source), Some(mut foreign_drag)) => {
+                        foreign_drag.source = source;
+                        Some(foreign_drag)
+                    }
+                    (Some(source), None) => Some(ForeignDrag::new(source)),
+                    (None, _) => None,
+                };
+            }
+        }
 
         let dragged_item_rect = if let DragDetectionState::Dragging {
             offset,
@@ -480,16 +2462,43 @@ impl DragDropUi {
             ..
         } = &self.detection_state
         {
-            Some(Rect::from_min_size(
-                pointer_pos.unwrap_or_default() + *offset,
-                *dragged_item_size,
-            ))
+            // While the item is over another list, we don't show a gap in this list
+            (!handed_off && self.lifted).then(|| {
+                Rect::from_min_size(
+                    self.floating_item_pos(pointer_pos.unwrap_or_default() + *offset),
+                    *dragged_item_size,
+                )
+            })
         } else {
-            None
+            self.foreign_drag.as_ref().map(|foreign| {
+                Rect::from_min_size(
+                    pointer_pos.unwrap_or_default() + foreign.source.offset,
+                    foreign.source.size,
+                )
+            })
         };
 
+        // Checked before drawing the items, so the dragged item can be tinted
+        self.hovering_remove_zone = self.detection_state.is_dragging()
+            && match (self.remove_zone, pointer_pos) {
+                (Some(remove_zone), Some(pointer_pos)) => remove_zone.contains(pointer_pos),
+                _ => false,
+            };
+
+        let was_dragging = self.detection_state.is_dragging();
+        // If the item was picked up during this frame, the keys have already been handled
+        let was_keyboard_dragging = self.detection_state.is_keyboard_dragging();
+
+        self.nesting_depth = nesting::enter_list(ui.ctx());
+        // The items and the gaps between them use the same spacing, so the gaps match the
+        // static layout
+        let ui_spacing = ui.spacing().item_spacing;
+        if let Some(spacing) = self.spacing {
+            ui.spacing_mut().item_spacing = spacing;
+        }
         let mut item_iter = ItemIterator::new(self, dragged_item_rect, *ui.layout());
         callback(ui, &mut item_iter);
+        ui.spacing_mut().item_spacing = ui_spacing;
 
         let ItemIterator {
             source_item,
@@ -497,9 +2506,111 @@ impl DragDropUi {
             mut closest_item,
             mark_next_as_closest_item,
             last_item,
+            first_item,
             hovering_last_item,
+            mut list_rect,
+            gap_rect,
+            mut swap_target,
+            locked_indices,
+            divider_indices,
+            pinned_indices,
+            group_indices,
+            group_extent,
+            item_ids,
+            item_rects,
+            duplicate_ids,
+            grid_target,
+            pointer_over_item,
+            into_target,
             ..
         } = item_iter;
+        nesting::leave_list(ui.ctx());
+
+        // An empty list has no items to drop an item from another list next to, so we allocate
+        // space it can be dropped into. It's inserted at index 0.
+        if let (Some(_), true) = (self.group, item_ids.is_empty()) {
+            let mut size = self.empty_drop_size.unwrap_or(Vec2::new(
+                ui.available_width(),
+                ui.spacing().interact_size.y,
+            ));
+            if let Some(foreign) = &self.foreign_drag {
+                // Make room for the item, like the gap in a list with items
+                size = size.max(foreign.source.size);
+            }
+            list_rect = ui.allocate_exact_size(size, Sense::hover()).0;
+        }
+
+        // A nested list started dragging in this frame, after our handle started the drag
+        if let DragDetectionState::Dragging {
+            programmatic: false,
+            started_at,
+            ..
+        } = self.detection_state
+        {
+            if started_at == ui.input(|i| i.time)
+                && DragClaim::claimed_by_nested_list(ui.ctx(), self.id, self.nesting_depth)
+            {
+                self.detection_state =
+                    DragDetectionState::Cancelled(CancellationReason::NestedListDragged);
+            }
+        }
+
+        if let (Some(placeholder), Some(gap_rect), true) = (
+            &mut callbacks.source_placeholder,
+            gap_rect,
+            self.detection_state.is_dragging(),
+        ) {
+            // The gap has already been allocated, so we only draw into it
+            let mut child = ui.child_ui(gap_rect, *ui.layout());
+            placeholder(&mut child, gap_rect.size());
+        }
+
+        self.hovered_since = match (pointer_over_item, self.detection_state.is_dragging()) {
+            (Some((idx, id)), true) => {
+                let now = ui.input(|i| i.time);
+                let since = match self.hovered_since {
+                    Some((hovered_id, since)) if hovered_id == id => since,
+                    _ => now,
+                };
+                if let Some(on_hover) = &mut callbacks.on_hover {
+                    on_hover(idx, Duration::from_secs_f64(now - since));
+                    // Keep calling the callback while the pointer rests on the item
+                    ui.ctx().request_repaint();
+                }
+                Some((id, since))
+            }
+            _ => None,
+        };
+
+        // In a grid, the cells after the last item move the item to the end of the list
+        let mark_next_as_closest_item = match (grid_target, last_item) {
+            (Some(target), Some((last_idx, _, pos)))
+                if target > last_idx && !hovering_last_item =>
+            {
+                Some((0.0, pos))
+            }
+            _ => mark_next_as_closest_item,
+        };
+
+        let reorder_mode = self.reorder_mode;
+        let dragging_group = !group_indices.is_empty();
+        let accepts = |from: usize, to: usize| {
+            // Other items skip over locked items like over pinned items, so only landing on them
+            // would displace them. Selected items dragged together don't skip them, so they
+            // shift all items between from and to, unless we swap.
+            let displaces_locked_item = locked_indices.iter().any(|&idx| match reorder_mode {
+                ReorderMode::Insert if dragging_group => {
+                    from != to && (from.min(to)..=from.max(to)).contains(&idx)
+                }
+                ReorderMode::Insert => from != to && idx == to,
+                ReorderMode::Swap => idx == to,
+            });
+            // Dividers stay in their order, so an item can't be swapped with them
+            let swaps_divider = reorder_mode == ReorderMode::Swap && divider_indices.contains(&to);
+            !displaces_locked_item
+                && !swaps_divider
+                && drop_filter.map_or(true, |filter| filter(from, to))
+        };
 
         // This is only some if we're hoving over the last item
         let hovering_last_item = if mark_next_as_closest_item.is_some() {
@@ -511,20 +2622,45 @@ impl DragDropUi {
             false
         };
 
-        let pointer_released = ui.input(|i| i.pointer.any_released());
         let should_update = closest_item.map(|i| i.1.is_some()).unwrap_or(false);
 
         // The cursor is not hovering over any item, so cancel
         if first_frame && !hovering_over_any_handle {
             self.detection_state =
-                DragDetectionState::Cancelled("Cursor not hovering over any item handle");
+                DragDetectionState::Cancelled(CancellationReason::NotHoveringHandle);
         }
 
-        let drag_phase_changed_this_frame = false;
-
         let hovering_item = closest_item.and_then(|i| i.1);
+        let (hovering_item, hovering_last_item) = self
+            .wrap_around_target(ui, pointer_pos, list_rect, first_item, last_item)
+            .unwrap_or((hovering_item, hovering_last_item));
+        // With pinned items, the gap is shown where the item actually lands, so the list
+        // updated during the drag looks the same as the gap
+        let (hovering_item, hovering_last_item) = match (&self.detection_state, hovering_item) {
+            // Items dragged along with a selection aren't in item_rects, we leave the gap then
+            (DragDetectionState::Dragging { id, source_idx, .. }, Some((hovering_idx, _, _)))
+                if reorder_mode == ReorderMode::Insert
+                    && !pinned_indices.is_empty()
+                    && item_rects.len() == item_ids.len() =>
+            {
+                let source_idx = source_item.map_or(*source_idx, |(idx, _)| idx);
+                pinned_gap(
+                    (source_idx, *id),
+                    hovering_idx + usize::from(hovering_last_item),
+                    &pinned_indices,
+                    &item_rects,
+                    first_item.map_or(0, |(idx, _, _)| idx),
+                )
+                .map_or((hovering_item, hovering_last_item), |(item, last)| {
+                    (Some(item), last)
+                })
+            }
+            _ => (hovering_item, hovering_last_item),
+        };
+        self.had_pinned_items = !pinned_indices.is_empty();
 
         if let DragDetectionState::Dragging {
+            id: dragged_id,
             closest_item: closest_out,
             source_idx: source_idx_out,
             hovering_idx: hovering_idx_out,
@@ -533,95 +2669,496 @@ impl DragDropUi {
             ..
         } = &mut self.detection_state
         {
+            if let Some(source_item) = source_item {
+                *source_idx_out = source_item.0;
+            }
             if let Some((hovering_idx, hovering_id, pos)) = hovering_item {
-                *closest_out = (hovering_id, pos);
-                *hovering_idx_out = hovering_idx;
-                *hovering_last_item_out = hovering_last_item;
+                let to = if hovering_last_item {
+                    hovering_idx + 1
+                } else {
+                    hovering_idx
+                };
+                // If the position is rejected, the gap moves to the closest accepted position.
+                // If we can't find one, e.g. while dragging a selection, or we swap, we stay at
+                // the last accepted position.
+                let target = if accepts(
+                    *source_idx_out,
+                    landing_index(reorder_mode, *source_idx_out, to),
+                ) {
+                    Some(((hovering_idx, hovering_id, pos), hovering_last_item))
+                } else if reorder_mode == ReorderMode::Insert && item_rects.len() == item_ids.len()
+                {
+                    nearest_accepted_gap(
+                        (*source_idx_out, *dragged_id),
+                        to,
+                        reorder_mode,
+                        &item_rects,
+                        first_item.map_or(0, |(idx, _, _)| idx),
+                        &accepts,
+                    )
+                } else {
+                    None
+                };
+                // While the item is over the center of another item, the gap stays where it was
+                if let (None, Some(((hovering_idx, hovering_id, pos), hovering_last_item))) =
+                    (into_target, target)
+                {
+                    *closest_out = (hovering_id, pos);
+                    *hovering_idx_out = hovering_idx;
+                    *hovering_last_item_out = hovering_last_item;
+                }
             }
             if let Some(pointer_pos) = pointer_pos {
                 *last_pointer_pos_out = pointer_pos;
             }
-            if let Some(source_item) = source_item {
-                *source_idx_out = source_item.0;
+            swap_target = swap_target
+                .filter(|(idx, _)| into_target.is_none() && accepts(*source_idx_out, *idx));
+        }
+
+        if let Some(foreign) = &mut self.foreign_drag {
+            if let Some((hovering_idx, hovering_id, pos)) = hovering_item {
+                foreign.closest_item = Some((hovering_id, pos));
+                foreign.hovering_idx = hovering_idx;
+                foreign.hovering_last_item = hovering_last_item;
             }
         }
 
+        self.last_list_rect = list_rect;
+
         if self.detection_state.is_dragging() {
             if let Some(pointer_pos) = pointer_pos {
-                // If we are in a ScrollArea, allow for scrolling while dragging
-                ui.scroll_to_rect(Rect::from_center_size(pointer_pos, Vec2::splat(50.0)), None);
+                if let Some(auto_scroll) = &self.auto_scroll {
+                    auto_scroll.scroll(ui, pointer_pos, list_rect);
+                } else {
+                    // If we are in a ScrollArea, allow for scrolling while dragging
+                    ui.scroll_to_rect(Rect::from_center_size(pointer_pos, Vec2::splat(50.0)), None);
+                }
             }
         }
 
-        let mut response = if !drag_phase_changed_this_frame {
-            if let DragDetectionState::Dragging {
-                source_idx,
-                hovering_idx,
-                hovering_last_item,
-                ..
-            } = self.detection_state
-            {
-                DragDropResponse {
-                    finished: false,
-                    update: Some(DragUpdate {
-                        from: source_idx,
-                        to: if hovering_last_item {
-                            hovering_idx + 1
-                        } else {
-                            hovering_idx
-                        },
-                    }),
-                    state: self.detection_state.clone(),
-                    cancellation_reason: None,
-                    has_changed: should_update,
+        let mut response = DragDropResponse::new(self.detection_state.clone(), self.reorder_mode);
+        if let DragDetectionState::Dragging {
+            source_idx,
+            hovering_idx,
+            hovering_last_item,
+            ..
+        } = self.detection_state
+        {
+            let (to, has_changed) = match self.reorder_mode {
+                ReorderMode::Insert => (
+                    if hovering_last_item {
+                        hovering_idx + 1
+                    } else {
+                        hovering_idx
+                    },
+                    should_update,
+                ),
+                ReorderMode::Swap => (swap_target.map_or(source_idx, |(idx, _rect)| idx), false),
+            };
+            response.update = Some(DragUpdate {
+                from: source_idx,
+                to,
+            });
+            response.has_changed = has_changed;
+        }
+
+        response.hovering_remove_zone = self.hovering_remove_zone;
+        self.last_item_ids.clone_from(&item_ids);
+        self.remapped_animations.clear();
+        response.item_ids = item_ids;
+        response.item_rects = item_rects;
+        response.duplicate_ids = duplicate_ids;
+        // Duplicate ids used to panic in debug builds, but they are easy to get with show_vec,
+        // e.g. for a list of numbers, so we only point them out
+        #[cfg(debug_assertions)]
+        if duplicate_ids {
+            ui.painter().error(
+                list_rect.left_top(),
+                "egui_dnd: duplicate item ids, use Dnd::show_vec_with_id to derive unique ids",
+            );
+        }
+        response.pinned = pinned_indices;
+        response.hovering_handle = hovering_over_any_handle;
+        response.floating_pos = dragged_item_rect
+            .filter(|_| self.detection_state.is_dragging())
+            .map(|rect| rect.min);
+        if let DragDetectionState::Dragging {
+            hovering_idx,
+            hovering_last_item,
+            ..
+        } = self.detection_state
+        {
+            response.drop_position = Some(match into_target {
+                Some((idx, _rect)) => DropPosition::Into(idx),
+                None if hovering_last_item => DropPosition::After(hovering_idx),
+                None => DropPosition::Before(hovering_idx),
+            });
+        }
+        let start_idx = match self.detection_state {
+            DragDetectionState::Dragging { start_idx, .. }
+            | DragDetectionState::KeyboardDragging { start_idx, .. } => Some(start_idx),
+            _ => None,
+        };
+        if let DragDetectionState::Dragging {
+            start_pointer_pos,
+            last_pointer_pos,
+            ..
+        } = self.detection_state
+        {
+            response.drag_delta = last_pointer_pos - start_pointer_pos;
+        }
+        response.started = (!was_dragging && self.detection_state.is_dragging())
+            || (!was_keyboard_dragging && self.detection_state.is_keyboard_dragging());
+
+        if !self.dragged_group.is_empty() {
+            // The list isn't updated while dragging, the items are moved when they are dropped
+            response.group = Some(group_indices);
+            response.has_changed = false;
+        }
+        if self.copy_on_drag && reorder_mode == ReorderMode::Insert {
+            // The dragged item stays where it is, the update is where the copy is inserted
+            response.copy = true;
+            response.has_changed = false;
+        }
+        self.dragged_group_extent = group_extent;
+        if let (true, Some(id), ReorderMode::Insert) = (
+            response.started && self.detection_state.is_dragging(),
+            self.detection_state.dragged_item(),
+            reorder_mode,
+        ) {
+            if self.selection.len() > 1 && self.selection.contains(&id) {
+                self.dragged_group = self.selection.clone();
+            }
+        }
+
+        // A drag started from code, e.g. when a button was clicked, shouldn't end with the
+        // release of that click
+        let pointer_released = pointer_released
+            && !(response.started
+                && matches!(
+                    self.detection_state,
+                    DragDetectionState::Dragging {
+                        programmatic: true,
+                        ..
+                    }
+                ));
+
+        // With manual commit, releasing the pointer only lets go of the item. Other releases,
+        // e.g. of the click on a confirm button, are ignored until the drag is committed.
+        let pointer_released =
+            if self.manual_commit && self.group.is_none() && self.detection_state.is_dragging() {
+                if pointer_released {
+                    self.awaiting_commit = true;
                 }
+                self.awaiting_commit && self.commit_requested
             } else {
-                DragDropResponse {
-                    finished: false,
-                    update: None,
-                    state: self.detection_state.clone(),
-                    cancellation_reason: None,
-                    has_changed: false,
-                }
-            }
-        } else {
-            DragDropResponse {
-                finished: false,
-                update: None,
-                state: self.detection_state.clone(),
-                cancellation_reason: None,
-                has_changed: false,
+                pointer_released
+            };
+        response.awaiting_commit = self.awaiting_commit && !pointer_released;
+
+        // Only the frame the focus is lost counts, since headless contexts never report focus
+        // and some platforms don't report it while dragging
+        let focused = ui.input(|i| i.focused);
+        let focus_lost = self.was_focused && !focused;
+        self.was_focused = focused;
+
+        if let DragDetectionState::Dragging {
+            id,
+            source_idx,
+            start_idx,
+            ..
+        } = self.detection_state
+        {
+            let escape = ui.input(|i| i.key_pressed(Key::Escape));
+            // If the window loses focus we may never see the pointer being released, so the
+            // item would keep floating until the pointer returns
+            let reason = if escape {
+                Some(CancellationReason::Escape)
+            } else if self.cancel_requested {
+                Some(CancellationReason::Programmatic)
+            } else if focus_lost {
+                Some(CancellationReason::FocusLost)
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                // Move the item back in case the source list was updated during the drag
+                response.update = Some(DragUpdate {
+                    from: source_idx,
+                    to: if start_idx > source_idx {
+                        start_idx + 1
+                    } else {
+                        start_idx
+                    },
+                });
+                response.has_changed = true;
+                response.cancellation_reason = Some(reason);
+
+                self.detection_state = DragDetectionState::TransitioningBackAfterDragFinished {
+                    dragged_item_size: self.detection_state.dragged_item_size(),
+                    id,
+                };
             }
-        };
+        }
+
+        if was_keyboard_dragging {
+            self.keyboard_reorder(ui, &mut response, source_item, last_item, &accepts);
+        }
 
         if pointer_released {
+            if handed_off && self.detection_state.is_dragging() {
+                // The other list reports the transfer, we must not update our list
+                response.finished = true;
+                response.moved_item = self.detection_state.dragged_item();
+                response.update = None;
+                self.detection_state = DragDetectionState::None;
+            } else if let (Some(group), Some(foreign)) = (self.group, self.foreign_drag.take()) {
+                response.finished = true;
+                response.moved_item = Some(foreign.source.item_id);
+                response.transfer = Some(DragTransfer {
+                    item_id: foreign.source.item_id,
+                    source_list: foreign.source.list_id,
+                    from: foreign.source.idx,
+                    target_list: group.list_id,
+                    to: foreign.target_idx(),
+                });
+                // The item will be shown in our list in the next frame, so we animate it from
+                // the pointer to its new position
+                self.detection_state = DragDetectionState::TransitioningBackAfterDragFinished {
+                    id: foreign.source.item_id,
+                    dragged_item_size: Some(foreign.source.size),
+                };
+            }
+        }
+
+        if pointer_released && self.detection_state.is_dragging() {
             if let Some(dragged_item) = self.detection_state.dragged_item() {
                 response.finished = true;
+                response.moved_item = Some(dragged_item);
 
-                self.detection_state = DragDetectionState::TransitioningBackAfterDragFinished {
-                    dragged_item_size: self.detection_state.dragged_item_size(),
-                    id: dragged_item,
+                if self.fling
+                    && reorder_mode == ReorderMode::Insert
+                    && into_target.is_none()
+                    && !self.hovering_remove_zone
+                {
+                    self.apply_fling(ui, &mut response, last_item, &accepts);
+                }
+
+                let rejected = response.update.as_ref().map_or(false, |update| {
+                    !accepts(
+                        update.from,
+                        landing_index(self.reorder_mode, update.from, update.to),
+                    )
+                });
+                let dropped_outside = !self.clamp_drop_outside
+                    && pointer_pos.map_or(false, |pos| !list_rect.contains(pos));
+                let too_short = match (self.min_drag_time, &self.detection_state) {
+                    (
+                        Some(min_drag_time),
+                        DragDetectionState::Dragging {
+                            started_at,
+                            programmatic: false,
+                            ..
+                        },
+                    ) => ui.input(|i| i.time) - started_at < min_drag_time.as_secs_f64(),
+                    _ => false,
                 };
+
+                if into_target.is_some() {
+                    // The item is moved into another item, so the list isn't reordered
+                    response.update = None;
+                }
+
+                if self.hovering_remove_zone {
+                    // The item is gone, so there is nothing to transition back
+                    response.removed = response.update.take().map(|update| update.from);
+                    self.detection_state = DragDetectionState::None;
+                } else if let (true, DragDetectionState::Dragging { start_idx, .. }) = (
+                    rejected || dropped_outside || too_short,
+                    self.detection_state.clone(),
+                ) {
+                    // Rejected can only happen if the list changed or the start position is
+                    // rejected, since the drag never moves to a rejected position
+                    response.finished = false;
+                    response.moved_item = None;
+                    response.update = response.update.take().map(|update| DragUpdate {
+                        from: update.from,
+                        to: if start_idx > update.from {
+                            start_idx + 1
+                        } else {
+                            start_idx
+                        },
+                    });
+                    response.has_changed = true;
+                    response.cancellation_reason = Some(if too_short {
+                        CancellationReason::TooShort
+                    } else if dropped_outside {
+                        CancellationReason::DroppedOutside
+                    } else {
+                        CancellationReason::Rejected
+                    });
+                    self.detection_state = DragDetectionState::TransitioningBackAfterDragFinished {
+                        dragged_item_size: self.detection_state.dragged_item_size(),
+                        id: dragged_item,
+                    };
+                } else if response.copy || !self.lifted {
+                    // The dragged item never left the list, so there is nothing to transition back
+                    self.detection_state = DragDetectionState::None;
+                } else {
+                    self.detection_state = DragDetectionState::TransitioningBackAfterDragFinished {
+                        dragged_item_size: self.detection_state.dragged_item_size(),
+                        id: dragged_item,
+                    };
+                }
             }
         }
 
-        ui.input(|input| {
-            if !input.pointer.any_down()
-                && !matches!(
-                    self.detection_state,
-                    DragDetectionState::TransitioningBackAfterDragFinished { .. }
-                )
-            {
-                if let DragDetectionState::Cancelled(msg) = self.detection_state {
-                    response.cancellation_reason = Some(msg);
+        // A keyboard drop keeps the focus, unless the user tabbed away, which we don't want to undo
+        if self.focus_after_drop
+            && response.finished
+            && !was_keyboard_dragging
+            && !handed_off
+            && response.removed.is_none()
+        {
+            self.pending_focus = response.moved_item;
+        }
+
+        if !self.animations_enabled()
+            && matches!(
+                self.detection_state,
+                DragDetectionState::TransitioningBackAfterDragFinished { .. }
+            )
+        {
+            // There is nothing to animate, so the drop is completed immediately
+            self.detection_state = DragDetectionState::None;
+        }
+
+        if !button_down
+            && !matches!(
+                self.detection_state,
+                DragDetectionState::TransitioningBackAfterDragFinished { .. }
+                    | DragDetectionState::KeyboardDragging { .. }
+                    | DragDetectionState::Dragging {
+                        programmatic: true,
+                        ..
+                    }
+            )
+        {
+            if let DragDetectionState::Cancelled(reason) = self.detection_state {
+                response.cancellation_reason = Some(reason);
+            }
+            self.detection_state = DragDetectionState::None;
+        }
+
+        if let (Some(group), Some(mut group_state)) = (self.group, group_state) {
+            if !pointer_released {
+                if let DragDetectionState::Dragging {
+                    id,
+                    source_idx,
+                    offset,
+                    dragged_item_size,
+                    ..
+                } = self.detection_state
+                {
+                    group_state.set_dragged(
+                        ui.ctx(),
+                        group.list_id,
+                        id,
+                        source_idx,
+                        offset,
+                        dragged_item_size,
+                    );
+                } else {
+                    group_state.clear_dragged(group.list_id);
+                }
+                if self.foreign_drag.is_some() {
+                    group_state.set_target(ui.ctx(), group.list_id);
+                } else {
+                    group_state.clear_target(group.list_id);
                 }
-                self.detection_state = DragDetectionState::None;
             }
-        });
+            group_state.store(ui.ctx(), group.group_id);
+        }
+
+        if let Some(stroke) = self.target_highlight {
+            // The item that is dropped onto, or the gap the item is inserted in, so the highlight
+            // always shows where the item lands
+            let target = into_target
+                .or(swap_target)
+                .map(|(_, rect)| rect)
+                .or(gap_rect
+                    .filter(|_| into_target.is_none() && self.reorder_mode == ReorderMode::Insert));
+            if let (true, Some(rect)) = (
+                self.detection_state.is_dragging() || self.foreign_drag.is_some(),
+                target,
+            ) {
+                ui.painter()
+                    .rect_stroke(rect, ui.visuals().widgets.hovered.rounding, stroke);
+            }
+        }
+
+        // The index of the gap in the list and the index the dragged item would end up at,
+        // which is None for an item dragged in from another list of the group
+        let gap = if let DragDetectionState::Dragging {
+            source_idx,
+            hovering_idx,
+            hovering_last_item,
+            ..
+        } = self.detection_state
+        {
+            let gap_index = hovering_idx + usize::from(hovering_last_item);
+            Some((
+                gap_index,
+                Some(landing_index(self.reorder_mode, source_idx, gap_index)),
+            ))
+        } else {
+            self.foreign_drag.as_ref().map(|foreign| {
+                (
+                    foreign.hovering_idx + usize::from(foreign.hovering_last_item),
+                    None,
+                )
+            })
+        };
+        let insertion_indicator = match (&callbacks.gap_indicator, gap) {
+            (Some(gap_indicator), Some((gap_index, target))) => gap_indicator(gap_index, target),
+            _ => self.insertion_indicator,
+        };
+        if let (Some(stroke), Some(gap_rect)) = (insertion_indicator, gap_rect) {
+            // Only draw while dragging so the indicator disappears on the frame the item is dropped
+            if self.detection_state.is_dragging() || self.foreign_drag.is_some() {
+                let points = if self.layout.is_horizontal(ui.layout()) {
+                    [gap_rect.center_top(), gap_rect.center_bottom()]
+                } else {
+                    [gap_rect.left_center(), gap_rect.right_center()]
+                };
+                ui.painter().line_segment(points, stroke);
+            }
+        }
+
+        // The item wasn't shown, so we don't start dragging it in some later frame
+        self.pending_drag = None;
+
+        // A drag that is about to start is cancelled as well, so it doesn't start until the
+        // pointer is pressed again
+        if self.cancel_requested && self.detection_state.is_evaluating_drag() {
+            self.detection_state = DragDetectionState::Cancelled(CancellationReason::Programmatic);
+        }
+        self.cancel_requested = false;
+        self.commit_requested = false;
+        if !self.detection_state.is_dragging() {
+            self.awaiting_commit = false;
+            self.lifted = false;
+        }
+
+        if !self.detection_state.is_dragging() {
+            self.dragged_group.clear();
+            self.dragged_group_extent = 0.0;
+        }
 
         // We are not over any target, cancel the drag
         if let DragDetectionState::CouldBeValidDrag = self.detection_state {
-            self.detection_state = DragDetectionState::Cancelled("Not hovering over any target");
+            self.detection_state =
+                DragDetectionState::Cancelled(CancellationReason::NotHoveringTarget);
         }
 
         // Repaint continuously while we are evaluating the drag
@@ -629,6 +3166,233 @@ impl DragDropUi {
             ui.ctx().request_repaint();
         }
 
+        #[cfg(feature = "testing")]
+        if let Some((id, to)) = self.simulated_drag.take() {
+            // The item has to be shown in this frame, like an item that is dragged by the user
+            if let Some(from) = response.item_ids.iter().position(|item| *item == id) {
+                response.update = Some(DragUpdate { from, to });
+                response.moved_item = Some(id);
+                response.started = true;
+                response.finished = true;
+                response.has_changed = true;
+            }
+        }
+
+        response.target_section = response.update.as_ref().map(|update| {
+            section_position(
+                self.reorder_mode,
+                update.from,
+                landing_index(self.reorder_mode, update.from, update.to),
+                &divider_indices,
+            )
+        });
+        response.events = response.collect_events(start_idx);
+        if response.started {
+            self.drag_session += 1;
+        }
+        if response.started || response.is_dragging() || response.finished {
+            response.drag_session_id = Some(self.drag_session);
+        }
+
+        let target = response
+            .update
+            .as_ref()
+            .filter(|_| {
+                (self.detection_state.is_dragging() || self.detection_state.is_keyboard_dragging())
+                    && response.cancellation_reason.is_none()
+                    && !response.finished
+            })
+            .map(|update| landing_index(self.reorder_mode, update.from, update.to));
+        if let (Some(target), Some(on_target_changed)) = (target, &mut callbacks.on_target_changed)
+        {
+            if self.provisional_target != Some(target) {
+                on_target_changed(self.provisional_target, target);
+            }
+        }
+        self.provisional_target = target;
+
+        if response.finished {
+            response.drop_pointer_pos = pointer_pos;
+        }
+        if response.is_dragging() || response.finished {
+            response.modifiers = ui.input(|i| i.modifiers);
+        }
+
+        // Only moves that changed the order are highlighted
+        let committed = response
+            .events
+            .iter()
+            .any(|event| matches!(event, DndEvent::Dropped { from, to } if from != to));
+        if let (Some(_), true, Some(id)) = (self.drop_highlight, committed, response.moved_item) {
+            self.highlighted_item = Some((id, ui.input(|i| i.time)));
+        }
+
         response
     }
+
+    /// Returns the closest item and whether the gap is after it if the pointer is dragged past
+    /// the end of the list (the first item) or past its start (after the last item).
+    #[allow(clippy::type_complexity)]
+    fn wrap_around_target(
+        &self,
+        ui: &Ui,
+        pointer_pos: Option<Pos2>,
+        list_rect: Rect,
+        first_item: Option<(usize, Id, Pos2)>,
+        last_item: Option<(usize, Id, Pos2)>,
+    ) -> Option<(Option<(usize, Id, Pos2)>, bool)> {
+        if !self.wrap_around
+            || self.reorder_mode != ReorderMode::Insert
+            || matches!(self.layout, DndLayout::Grid { .. })
+            || !self.detection_state.is_dragging()
+        {
+            return None;
+        }
+        let pointer_pos = pointer_pos?;
+        let (pointer, start, end) = if self.layout.is_horizontal(ui.layout()) {
+            (pointer_pos.x, list_rect.min.x, list_rect.max.x)
+        } else {
+            (pointer_pos.y, list_rect.min.y, list_rect.max.y)
+        };
+        if pointer > end {
+            Some((first_item, false))
+        } else if pointer < start {
+            Some((last_item, true))
+        } else {
+            None
+        }
+    }
+
+    /// Moves the dropped item further in the direction the pointer moved if it was released
+    /// faster than [FLING_SPEED].
+    fn apply_fling(
+        &self,
+        ui: &Ui,
+        response: &mut DragDropResponse,
+        last_item: Option<(usize, Id, Pos2)>,
+        accepts: &dyn Fn(usize, usize) -> bool,
+    ) {
+        let (Some(update), Some((last_idx, ..))) = (&mut response.update, last_item) else {
+            return;
+        };
+        let velocity = ui.input(|i| i.pointer.velocity());
+        let item_size = self.detection_state.dragged_item_size().unwrap_or_default();
+        let (speed, item_size) = if self.layout.is_horizontal(ui.layout()) {
+            (velocity.x, item_size.x)
+        } else {
+            (velocity.y, item_size.y)
+        };
+        if speed.abs() <= FLING_SPEED || item_size <= 0.0 {
+            return;
+        }
+
+        // The faster the pointer, the more items the dragged item travels past
+        let distance = (speed.abs() * FLING_TIME / item_size).round() as usize;
+        let landing = landing_index(self.reorder_mode, update.from, update.to);
+        let landing = if speed > 0.0 {
+            (landing + distance).min(last_idx)
+        } else {
+            landing.saturating_sub(distance)
+        };
+        if accepts(update.from, landing) {
+            update.to = if landing > update.from {
+                landing + 1
+            } else {
+                landing
+            };
+            response.has_changed = true;
+        }
+    }
+
+    /// Moves, drops or cancels the item that was picked up with the keyboard.
+    fn keyboard_reorder(
+        &mut self,
+        ui: &Ui,
+        response: &mut DragDropResponse,
+        source_item: Option<(usize, Id)>,
+        last_item: Option<(usize, Id, Pos2)>,
+        accepts: &dyn Fn(usize, usize) -> bool,
+    ) {
+        let DragDetectionState::KeyboardDragging {
+            id,
+            source_idx,
+            start_idx,
+            target_idx,
+        } = &mut self.detection_state
+        else {
+            return;
+        };
+        let id = *id;
+
+        if let Some((idx, _)) = source_item {
+            *source_idx = idx;
+        }
+        let last_idx = last_item.map_or(0, |(idx, ..)| idx);
+
+        let (step, drop, cancel) = ui.input(|i| {
+            let step = if i.key_pressed(Key::ArrowUp) || i.key_pressed(Key::ArrowLeft) {
+                -1
+            } else if i.key_pressed(Key::ArrowDown) || i.key_pressed(Key::ArrowRight) {
+                1
+            } else {
+                0
+            };
+            (
+                step,
+                i.key_pressed(Key::Space) || i.key_pressed(Key::Enter),
+                i.key_pressed(Key::Escape),
+            )
+        });
+        // If the handle lost focus, e.g. because the user tabbed away, we drop the item
+        let lost_focus = !ui.memory(|mem| mem.has_focus(keyboard_focus_id(id)));
+
+        // Skip positions rejected by the drop filter
+        let mut new_target = *target_idx;
+        let mut candidate = *target_idx;
+        while step != 0 {
+            match candidate.checked_add_signed(step) {
+                Some(next) if next <= last_idx => candidate = next,
+                _ => break,
+            }
+            if candidate == *source_idx || accepts(*source_idx, candidate) {
+                new_target = candidate;
+                break;
+            }
+        }
+        let has_changed = new_target != *target_idx;
+        *target_idx = new_target;
+
+        let (from, target, start) = (*source_idx, *target_idx, *start_idx);
+        let reorder_mode = self.reorder_mode;
+        let to = |target: usize| match reorder_mode {
+            ReorderMode::Insert if target > from => target + 1,
+            _ => target,
+        };
+
+        if cancel || self.cancel_requested {
+            response.update = Some(DragUpdate {
+                from,
+                to: to(start),
+            });
+            response.has_changed = true;
+            response.cancellation_reason = Some(if cancel {
+                CancellationReason::Escape
+            } else {
+                CancellationReason::Programmatic
+            });
+            self.detection_state = DragDetectionState::None;
+        } else {
+            response.update = Some(DragUpdate {
+                from,
+                to: to(target),
+            });
+            response.has_changed = has_changed;
+            if drop || lost_focus {
+                response.finished = true;
+                response.moved_item = Some(id);
+                self.detection_state = DragDetectionState::None;
+            }
+        }
+        response.state = self.detection_state.clone();
+    }
 }
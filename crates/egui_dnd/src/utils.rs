@@ -1,3 +1,7 @@
+use egui::{Id, Pos2, Rect};
+
+use crate::DragDropItem;
+
 /// Move an item in a slice according to the drag and drop logic.
 ///
 /// Rotates the section of the slice between `source_idx` and `target_idx` such that the item
@@ -41,3 +45,224 @@ pub fn shift_vec<T>(source_idx: usize, target_idx: usize, vec: &mut [T]) {
         );
     }
 }
+
+/// Move multiple items in a slice to `target_idx`, keeping their relative order.
+///
+/// The items at `source_indices` end up next to each other, in front of the item that was at
+/// `target_idx` before, or at the end if `target_idx == len()`. This is how the selected
+/// items are moved when they are dragged together, see [crate::Dnd::with_selection].
+///
+/// # Example
+///
+/// ```rust
+/// use egui_dnd::utils::shift_vec_group;
+///
+/// let mut v = vec![1, 2, 3, 4, 5];
+/// shift_vec_group(&[0, 2], 4, &mut v);
+/// assert_eq!(v, [2, 4, 1, 3, 5]);
+/// shift_vec_group(&[1, 4], 0, &mut v);
+/// assert_eq!(v, [4, 5, 2, 1, 3]);
+/// ```
+///
+/// # Panics
+/// Panics if any index in `source_indices` is `>= len()` or `target_idx > len()`
+pub fn shift_vec_group<T>(source_indices: &[usize], target_idx: usize, vec: &mut [T]) {
+    let mut indices = source_indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+
+    // Items in front of the target are moved starting with the last one, so the indices of
+    // the items we still have to move don't change
+    let mut target = target_idx;
+    for &idx in indices.iter().filter(|&&idx| idx < target_idx).rev() {
+        shift_vec(idx, target, vec);
+        target -= 1;
+    }
+    let mut target = target_idx;
+    for &idx in indices.iter().filter(|&&idx| idx >= target_idx) {
+        shift_vec(idx, target, vec);
+        target += 1;
+    }
+}
+
+/// Move an item in a slice like [shift_vec], but the items at the `pinned` indices keep their
+/// index. The other items move past them as if they weren't there, see
+/// [crate::Dnd::with_pinned_items]. If the item would land on a pinned item, it's moved next
+/// to it instead. The item at `source_idx` is moved even if it's pinned.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_dnd::utils::shift_vec_pinned;
+///
+/// let mut v = vec![1, 2, 3, 4, 5];
+/// shift_vec_pinned(0, 4, &[2], &mut v);
+/// assert_eq!(v, [2, 4, 3, 1, 5]);
+/// shift_vec_pinned(4, 0, &[2], &mut v);
+/// assert_eq!(v, [5, 2, 3, 4, 1]);
+/// ```
+///
+/// # Panics
+/// Panics if `source_idx >= len()` or `target_idx > len()`
+pub fn shift_vec_pinned<T>(source_idx: usize, target_idx: usize, pinned: &[usize], vec: &mut [T]) {
+    assert!(
+        source_idx < vec.len() && target_idx <= vec.len(),
+        "Failed to move item from index {} to index {}. Slice has {} elements",
+        source_idx,
+        target_idx,
+        vec.len()
+    );
+    let free: Vec<usize> = (0..vec.len())
+        .filter(|idx| *idx == source_idx || !pinned.contains(idx))
+        .collect();
+    // The indices within the items that aren't pinned
+    let from = free.iter().filter(|&&idx| idx < source_idx).count();
+    let to = free.iter().filter(|&&idx| idx < target_idx).count();
+
+    // Same as shift_vec, but only on the items that aren't pinned
+    if from < to {
+        for i in from..to - 1 {
+            vec.swap(free[i], free[i + 1]);
+        }
+    } else {
+        for i in (to..from).rev() {
+            vec.swap(free[i], free[i + 1]);
+        }
+    }
+}
+
+/// Returns the index the item at `source_idx` ends up at when [shift_vec_pinned] moves it to
+/// `target_idx`, in a slice with `len` items.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_dnd::utils::{pinned_landing_index, shift_vec_pinned};
+///
+/// // Moving the item behind the pinned item at index 2 is the same as moving it in front of it
+/// assert_eq!(pinned_landing_index(0, 2, &[2], 5), 1);
+/// assert_eq!(pinned_landing_index(0, 3, &[2], 5), 1);
+/// assert_eq!(pinned_landing_index(0, 4, &[2], 5), 3);
+/// assert_eq!(pinned_landing_index(4, 2, &[2], 5), 3);
+///
+/// let mut v = vec![1, 2, 3, 4, 5];
+/// shift_vec_pinned(0, 4, &[2], &mut v);
+/// assert_eq!(v[3], 1);
+/// ```
+///
+/// # Panics
+/// Panics if `source_idx >= len` or `target_idx > len`
+pub fn pinned_landing_index(
+    source_idx: usize,
+    target_idx: usize,
+    pinned: &[usize],
+    len: usize,
+) -> usize {
+    assert!(
+        source_idx < len && target_idx <= len,
+        "Failed to move item from index {} to index {}. Slice has {} elements",
+        source_idx,
+        target_idx,
+        len
+    );
+    let free: Vec<usize> = (0..len)
+        .filter(|idx| *idx == source_idx || !pinned.contains(idx))
+        .collect();
+    let from = free.iter().filter(|&&idx| idx < source_idx).count();
+    let to = free.iter().filter(|&&idx| idx < target_idx).count();
+    if from < to {
+        free[to - 1]
+    } else {
+        free[to]
+    }
+}
+
+/// Returns the [DragDropItem::id] of each item, e.g. to save the order of a list.
+/// With egui's `serde` feature enabled, the ids can be serialized and restored with
+/// [sort_vec_by_ids] later. Since an [Id] is a hash of the item, items have to hash the same
+/// between sessions for this to work.
+///
+/// # Example
+///
+/// ```rust
+/// use egui_dnd::utils::{item_ids, sort_vec_by_ids};
+///
+/// let saved_order = item_ids(&["c", "a", "b"]);
+///
+/// let mut v = vec!["a", "b", "c", "d"];
+/// sort_vec_by_ids(&mut v, &saved_order);
+/// assert_eq!(v, ["c", "a", "b", "d"]);
+/// ```
+pub fn item_ids<T: DragDropItem>(items: &[T]) -> Vec<Id> {
+    items.iter().map(DragDropItem::id).collect()
+}
+
+/// Sorts the items in the order of `ids`, see [item_ids].
+/// Items whose id is not in `ids` are moved to the end, keeping their relative order.
+pub fn sort_vec_by_ids<T: DragDropItem>(vec: &mut [T], ids: &[Id]) {
+    vec.sort_by_cached_key(|item| {
+        let id = item.id();
+        ids.iter()
+            .position(|saved| *saved == id)
+            .unwrap_or(usize::MAX)
+    });
+}
+
+/// The axis the items of a list are laid out along, see [estimate_target_index].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragAxis {
+    /// The items are below each other
+    Vertical,
+    /// The items are next to each other
+    Horizontal,
+}
+
+/// Estimates where an item would be moved to if it was dropped at `pointer`, without showing
+/// the list, e.g. for tests or to validate moves.
+///
+/// `item_rects` are the rects of all items in the list, including the dragged item at index
+/// `dragged`. The item is inserted in front of the first other item whose center is behind
+/// the pointer along `axis`, or at the end of the list.
+/// The result can be passed to [shift_vec] as `target_idx`, like [crate::DragUpdate::to].
+///
+/// This is an approximation of what the list does while dragging. The list compares the
+/// center of the floating item instead of the pointer with the items and the gap it opened in
+/// the last frame, and takes [crate::Dnd::with_reorder_hysteresis], pinned and locked items
+/// and [crate::DndLayout::Grid] into account, so it can pick a different index, especially
+/// close to the midpoint between two items.
+///
+/// # Example
+///
+/// ```rust
+/// use egui::{pos2, vec2, Rect};
+/// use egui_dnd::utils::{estimate_target_index, shift_vec, DragAxis};
+///
+/// let rects: Vec<_> = (0..3)
+///     .map(|i| Rect::from_min_size(pos2(0.0, i as f32 * 10.0), vec2(100.0, 10.0)))
+///     .collect();
+///
+/// assert_eq!(estimate_target_index(&rects, pos2(50.0, 17.0), 0, DragAxis::Vertical), 2);
+/// assert_eq!(estimate_target_index(&rects, pos2(50.0, 27.0), 0, DragAxis::Vertical), 3);
+/// assert_eq!(estimate_target_index(&rects, pos2(50.0, 2.0), 2, DragAxis::Vertical), 0);
+///
+/// let mut v = vec!["a", "b", "c"];
+/// shift_vec(0, estimate_target_index(&rects, pos2(50.0, 17.0), 0, DragAxis::Vertical), &mut v);
+/// assert_eq!(v, ["b", "a", "c"]);
+/// ```
+pub fn estimate_target_index(
+    item_rects: &[Rect],
+    pointer: Pos2,
+    dragged: usize,
+    axis: DragAxis,
+) -> usize {
+    let main_axis = |pos: Pos2| match axis {
+        DragAxis::Vertical => pos.y,
+        DragAxis::Horizontal => pos.x,
+    };
+    item_rects
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != dragged)
+        .find(|(_, rect)| main_axis(pointer) < main_axis(rect.center()))
+        .map_or(item_rects.len(), |(idx, _)| idx)
+}
@@ -0,0 +1,48 @@
+//! A button in a handle still gets clicked if the drag was started by
+//! [egui_dnd::DragDropConfig::click_tolerance_timeout] and the pointer didn't move.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{move_to, press, release, Harness};
+use egui::{Id, Ui};
+use egui_dnd::{dnd, DragDropResponse};
+
+/// Shows the list and returns its response and whether the button of `"a"` was clicked
+fn show(ui: &mut Ui, items: &mut [&'static str]) -> (DragDropResponse, bool) {
+    let mut clicked = false;
+    let response = dnd(ui, "list").show_vec(items, |ui, item, handle, _state| {
+        handle.ui(ui, |ui| {
+            if ui.button(*item).clicked() && *item == "a" {
+                clicked = true;
+            }
+        });
+    });
+    (response, clicked)
+}
+
+#[test]
+fn button_in_handle_is_clicked_after_the_timeout() {
+    let mut harness = Harness::new();
+    let mut items = vec!["a", "b", "c"];
+    let (response, _) = harness.frame(Vec::new(), |ui| show(ui, &mut items));
+    let pos = response.item_rect(Id::new("a")).unwrap().center();
+    let b = response.item_rect(Id::new("b")).unwrap();
+
+    harness.frame(move_to(pos), |ui| show(ui, &mut items));
+    harness.frame(press(pos), |ui| show(ui, &mut items));
+    // The timeout is measured in real time
+    std::thread::sleep(Duration::from_millis(300));
+    let (response, clicked) = harness.frame(Vec::new(), |ui| show(ui, &mut items));
+    assert!(response.is_dragging());
+    assert!(!clicked);
+
+    let (response, _) = harness.frame(Vec::new(), |ui| show(ui, &mut items));
+    // The item stays in the list while the pointer doesn't move, so the other items don't move
+    assert_eq!(response.item_rect(Id::new("b")), Some(b));
+
+    let (_, clicked) = harness.frame(release(pos), |ui| show(ui, &mut items));
+    assert!(clicked);
+    assert_eq!(items, ["a", "b", "c"]);
+}
@@ -0,0 +1,64 @@
+//! Runs frames of a headless egui context with simulated pointer input.
+
+#![allow(dead_code)]
+
+use egui::{pos2, vec2, Context, Event, Modifiers, PointerButton, Pos2, RawInput, Rect, Ui};
+
+pub struct Harness {
+    pub ctx: Context,
+    time: f64,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self {
+            ctx: Context::default(),
+            time: 0.0,
+        }
+    }
+
+    /// Runs one frame with the given input events, showing `add_contents` in a CentralPanel.
+    /// Returns what `add_contents` returned.
+    pub fn frame<R>(&mut self, events: Vec<Event>, add_contents: impl FnOnce(&mut Ui) -> R) -> R {
+        self.time += 1.0 / 60.0;
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(pos2(0.0, 0.0), vec2(800.0, 600.0))),
+            time: Some(self.time),
+            events,
+            ..Default::default()
+        };
+        let mut result = None;
+        let _ = self.ctx.run(input, |ctx| {
+            result = Some(egui::CentralPanel::default().show(ctx, add_contents).inner);
+        });
+        result.expect("the frame was run")
+    }
+}
+
+pub fn move_to(pos: Pos2) -> Vec<Event> {
+    vec![Event::PointerMoved(pos)]
+}
+
+pub fn press(pos: Pos2) -> Vec<Event> {
+    vec![
+        Event::PointerMoved(pos),
+        Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed: true,
+            modifiers: Modifiers::NONE,
+        },
+    ]
+}
+
+pub fn release(pos: Pos2) -> Vec<Event> {
+    vec![
+        Event::PointerMoved(pos),
+        Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed: false,
+            modifiers: Modifiers::NONE,
+        },
+    ]
+}
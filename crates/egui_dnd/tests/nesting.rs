@@ -0,0 +1,96 @@
+//! Two levels of nested lists, where the handles of the inner list are inside the handles of
+//! the outer list, so pressing an inner handle also presses the outer one.
+
+mod common;
+
+use common::{move_to, press, Harness};
+use egui::{vec2, Id, Pos2, Ui};
+use egui_dnd::{dnd, DragDropResponse};
+
+struct Lists {
+    outer: Vec<&'static str>,
+    inner: Vec<&'static str>,
+}
+
+struct Responses {
+    outer: DragDropResponse,
+    inner: DragDropResponse,
+}
+
+fn show(ui: &mut Ui, lists: &mut Lists) -> Responses {
+    let Lists { outer, inner } = lists;
+    let mut inner_response = None;
+    let outer_response = dnd(ui, "outer").show_vec(outer, |ui, item, handle, _state| {
+        handle.ui(ui, |ui| {
+            ui.label(*item);
+            if *item == "group" {
+                inner_response = Some(dnd(ui, "inner").show_vec(
+                    inner,
+                    |ui, item, handle, _state| {
+                        handle.ui(ui, |ui| {
+                            ui.label(*item);
+                        });
+                    },
+                ));
+            }
+        });
+    });
+    Responses {
+        outer: outer_response,
+        inner: inner_response.expect("the inner list is shown"),
+    }
+}
+
+/// Presses the pointer at `pos` and moves it past the click threshold
+fn drag_at(harness: &mut Harness, lists: &mut Lists, pos: Pos2) -> Responses {
+    harness.frame(move_to(pos), |ui| show(ui, lists));
+    harness.frame(press(pos), |ui| show(ui, lists));
+    for step in 1..3 {
+        harness.frame(move_to(pos + vec2(5.0 * step as f32, 0.0)), |ui| {
+            show(ui, lists)
+        });
+    }
+    harness.frame(move_to(pos + vec2(15.0, 0.0)), |ui| show(ui, lists))
+}
+
+fn lists() -> Lists {
+    Lists {
+        outer: vec!["group", "other"],
+        inner: vec!["inner_a", "inner_b"],
+    }
+}
+
+/// Shows the lists once and returns their responses, e.g. to get the rects of the items
+fn layout(harness: &mut Harness, lists: &mut Lists) -> Responses {
+    harness.frame(Vec::new(), |ui| show(ui, lists))
+}
+
+#[test]
+fn inner_list_claims_the_drag() {
+    let mut harness = Harness::new();
+    let mut lists = lists();
+    let inner_a = layout(&mut harness, &mut lists)
+        .inner
+        .item_rect(Id::new("inner_a"))
+        .unwrap();
+
+    let responses = drag_at(&mut harness, &mut lists, inner_a.center());
+
+    assert_eq!(responses.inner.dragged_item_id(), Some(Id::new("inner_a")));
+    assert!(!responses.outer.is_dragging());
+}
+
+#[test]
+fn outer_list_drags_outside_of_the_inner_list() {
+    let mut harness = Harness::new();
+    let mut lists = lists();
+    let other = layout(&mut harness, &mut lists)
+        .outer
+        .item_rect(Id::new("other"))
+        .unwrap();
+
+    let responses = drag_at(&mut harness, &mut lists, other.center());
+
+    assert_eq!(responses.outer.dragged_item_id(), Some(Id::new("other")));
+    assert!(!responses.inner.is_dragging());
+}
@@ -0,0 +1,75 @@
+//! Drops items with [egui_dnd::Dnd::simulate_drag] and checks the response.
+
+mod common;
+
+use common::Harness;
+use egui::{Id, Ui};
+use egui_dnd::{dnd, DragDropResponse, ReorderMode};
+
+fn show(ui: &mut Ui, items: &mut [&'static str], drag: Option<(&str, usize)>) -> DragDropResponse {
+    show_with_mode(ui, items, drag, ReorderMode::Insert)
+}
+
+fn show_with_mode(
+    ui: &mut Ui,
+    items: &mut [&'static str],
+    drag: Option<(&str, usize)>,
+    reorder_mode: ReorderMode,
+) -> DragDropResponse {
+    let mut dnd = dnd(ui, "list").with_reorder_mode(reorder_mode);
+    if let Some((item, to)) = drag {
+        dnd = dnd.simulate_drag(Id::new(item), to);
+    }
+    dnd.show_vec(items, |ui, item, handle, _state| {
+        handle.ui(ui, |ui| {
+            ui.label(*item);
+        });
+    })
+}
+
+#[test]
+fn simulated_drop_updates_the_vec() {
+    let mut harness = Harness::new();
+    let mut items = vec!["a", "b", "c"];
+
+    let response = harness.frame(vec![], |ui| show(ui, &mut items, Some(("a", 3))));
+
+    assert_eq!(items, ["b", "c", "a"]);
+    assert!(response.is_drag_finished());
+    let update = response.final_update().expect("the item was dropped");
+    assert_eq!((update.from, update.to), (0, 3));
+    // The item is inserted in front of index 3, after removing it from index 0
+    assert_eq!(response.current_target_index(), Some(2));
+
+    // Nothing is dragged in the next frame
+    let response = harness.frame(vec![], |ui| show(ui, &mut items, None));
+    assert!(!response.is_dragging());
+    assert!(response.final_update().is_none());
+    assert_eq!(items, ["b", "c", "a"]);
+}
+
+#[test]
+fn simulated_drop_in_swap_mode() {
+    let mut harness = Harness::new();
+    let mut items = vec!["a", "b", "c"];
+
+    let response = harness.frame(vec![], |ui| {
+        show_with_mode(ui, &mut items, Some(("a", 2)), ReorderMode::Swap)
+    });
+
+    assert_eq!(items, ["c", "b", "a"]);
+    // The swapped item lands on the index of the item it was dropped onto
+    assert_eq!(response.current_target_index(), Some(2));
+}
+
+#[test]
+fn simulated_drag_of_unknown_item_does_nothing() {
+    let mut harness = Harness::new();
+    let mut items = vec!["a", "b", "c"];
+
+    let response = harness.frame(vec![], |ui| show(ui, &mut items, Some(("d", 0))));
+
+    assert_eq!(items, ["a", "b", "c"]);
+    assert!(!response.is_drag_finished());
+    assert_eq!(response.current_target_index(), None);
+}
@@ -0,0 +1,153 @@
+//! Edge cases of the helpers in `egui_dnd::utils` that aren't covered by the doc examples.
+
+use egui::{pos2, vec2, Rect};
+use egui_dnd::utils::{
+    estimate_target_index, pinned_landing_index, shift_vec, shift_vec_group, shift_vec_pinned,
+    DragAxis,
+};
+
+#[test]
+fn shift_vec_group_keeps_relative_order() {
+    let mut v = vec!["a", "b", "c", "d"];
+    // The indices don't have to be sorted
+    shift_vec_group(&[3, 0], 2, &mut v);
+    assert_eq!(v, ["b", "a", "d", "c"]);
+}
+
+#[test]
+fn shift_vec_group_to_the_ends() {
+    let mut v = vec![1, 2, 3, 4];
+    shift_vec_group(&[0, 1], 4, &mut v);
+    assert_eq!(v, [3, 4, 1, 2]);
+    shift_vec_group(&[2, 3], 0, &mut v);
+    assert_eq!(v, [1, 2, 3, 4]);
+}
+
+#[test]
+fn shift_vec_group_ignores_duplicates() {
+    let mut v = vec![1, 2, 3, 4];
+    shift_vec_group(&[1, 1], 3, &mut v);
+    assert_eq!(v, [1, 3, 2, 4]);
+}
+
+#[test]
+fn shift_vec_group_without_items() {
+    let mut v = vec![1, 2, 3];
+    shift_vec_group(&[], 0, &mut v);
+    assert_eq!(v, [1, 2, 3]);
+}
+
+#[test]
+fn shift_vec_group_single_item_matches_shift_vec() {
+    for source in 0..4 {
+        for target in 0..=4 {
+            let mut group = vec![1, 2, 3, 4];
+            let mut single = group.clone();
+            shift_vec_group(&[source], target, &mut group);
+            shift_vec(source, target, &mut single);
+            assert_eq!(group, single, "moving {source} to {target}");
+        }
+    }
+}
+
+#[test]
+fn shift_vec_pinned_without_pinned_matches_shift_vec() {
+    for source in 0..4 {
+        for target in 0..=4 {
+            let mut pinned = vec![1, 2, 3, 4];
+            let mut single = pinned.clone();
+            shift_vec_pinned(source, target, &[], &mut pinned);
+            shift_vec(source, target, &mut single);
+            assert_eq!(pinned, single, "moving {source} to {target}");
+        }
+    }
+}
+
+#[test]
+fn shift_vec_pinned_onto_pinned_item() {
+    let mut v = vec![1, 2, 3, 4, 5];
+    shift_vec_pinned(0, 3, &[2], &mut v);
+    assert_eq!(v, [2, 1, 3, 4, 5]);
+}
+
+#[test]
+fn shift_vec_pinned_moves_pinned_source() {
+    let mut v = vec![1, 2, 3, 4, 5];
+    shift_vec_pinned(2, 0, &[2], &mut v);
+    assert_eq!(v, [3, 1, 2, 4, 5]);
+}
+
+#[test]
+#[should_panic]
+fn shift_vec_pinned_target_out_of_bounds() {
+    let mut v = vec![1, 2, 3];
+    shift_vec_pinned(0, 4, &[1], &mut v);
+}
+
+#[test]
+fn pinned_landing_index_matches_shift_vec_pinned() {
+    let pinned = [1, 3];
+    let original = vec![1, 2, 3, 4, 5];
+    for source in 0..original.len() {
+        for target in 0..=original.len() {
+            let mut v = original.clone();
+            shift_vec_pinned(source, target, &pinned, &mut v);
+            let landing = pinned_landing_index(source, target, &pinned, original.len());
+            assert_eq!(v[landing], original[source], "moving {source} to {target}");
+            for &idx in pinned.iter().filter(|idx| **idx != source) {
+                if idx != landing {
+                    assert_eq!(v[idx], original[idx], "moving {source} to {target}");
+                }
+            }
+        }
+    }
+}
+
+fn rects(count: usize, size: f32) -> Vec<Rect> {
+    (0..count)
+        .map(|i| Rect::from_min_size(pos2(0.0, i as f32 * size), vec2(100.0, size)))
+        .collect()
+}
+
+#[test]
+fn estimate_target_index_empty_list() {
+    assert_eq!(
+        estimate_target_index(&[], pos2(0.0, 0.0), 0, DragAxis::Vertical),
+        0
+    );
+}
+
+#[test]
+fn estimate_target_index_in_front_of_first_item() {
+    let rects = rects(3, 10.0);
+    assert_eq!(
+        estimate_target_index(&rects, pos2(50.0, -5.0), 1, DragAxis::Vertical),
+        0
+    );
+}
+
+#[test]
+fn estimate_target_index_skips_dragged_item() {
+    let rects = rects(3, 10.0);
+    // The pointer is in front of the center of the dragged item, which isn't a target
+    assert_eq!(
+        estimate_target_index(&rects, pos2(50.0, 12.0), 1, DragAxis::Vertical),
+        2
+    );
+}
+
+#[test]
+fn estimate_target_index_horizontal() {
+    let rects: Vec<_> = (0..3)
+        .map(|i| Rect::from_min_size(pos2(i as f32 * 10.0, 0.0), vec2(10.0, 100.0)))
+        .collect();
+    assert_eq!(
+        estimate_target_index(&rects, pos2(17.0, 90.0), 0, DragAxis::Horizontal),
+        2
+    );
+    // Only the main axis is used
+    assert_eq!(
+        estimate_target_index(&rects, pos2(17.0, 90.0), 0, DragAxis::Vertical),
+        3
+    );
+}